@@ -7,7 +7,7 @@ use revolt::{
     http::RevoltHttp,
     models::{
         authentication::Authentication,
-        event::{ClientToServerEvent, ServerToClientEvent},
+        event::ServerToClientEvent,
         message::Message,
         payload::SendMessagePayload,
     },
@@ -28,13 +28,8 @@ async fn main() -> Result {
 
     // Create an http and ws instances
     let http = RevoltHttp::new(Authentication::BotToken(token.clone()));
-    let mut ws = RevoltWs::connect().await?;
-
-    // Send authentication event to start receiving events
-    ws.send(ClientToServerEvent::Authenticate {
-        token: token.clone(),
-    })
-    .await?;
+    // The gateway authenticates by itself and reconnects when the connection drops
+    let mut ws = RevoltWs::connect(token).await?;
 
     // Create the context
     let ctx = Arc::new(Context { http });
@@ -0,0 +1,157 @@
+use criterion::{
+    black_box, criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, Criterion,
+};
+use revolt_gateway::{Direction, EventFilter, Format, Frame, FrameData};
+use revolt_models::event::{KnownEvent, ServerToClientEvent};
+use serde_json::{json, Value};
+
+const USERS: usize = 5000;
+
+/// `Ready` payload shaped like one received by a bot in a few large servers.
+fn ready() -> String {
+    let users: Vec<Value> = (0..USERS)
+        .map(|i| {
+            json!({
+                "_id": format!("01G{:023}", i),
+                "username": format!("user{}", i),
+                "avatar": {
+                    "_id": format!("avatar{}", i),
+                    "tag": "avatars",
+                    "filename": "avatar.png",
+                    "metadata": { "type": "Image", "width": 256, "height": 256 },
+                    "content_type": "image/png",
+                    "size": 12345
+                },
+                "badges": 0,
+                "status": { "text": "Hello there", "presence": "Online" },
+                "relationship": "None",
+                "online": i % 3 == 0
+            })
+        })
+        .collect();
+
+    let members: Vec<Value> = (0..USERS)
+        .map(|i| {
+            json!({
+                "_id": { "server": "01FVB1ZGCPS8TJ4PD4P7NAFDZA", "user": format!("01G{:023}", i) },
+                "joined_at": "2022-07-01T12:00:00.000Z",
+                "nickname": format!("nick{}", i),
+                "roles": ["01FVB1ZGCPS8TJ4PD4P7NAFDZB", "01FVB1ZGCPS8TJ4PD4P7NAFDZC"]
+            })
+        })
+        .collect();
+
+    json!({
+        "type": "Ready",
+        "users": users,
+        "servers": [],
+        "channels": [],
+        "members": members,
+        "emojis": []
+    })
+    .to_string()
+}
+
+fn message() -> String {
+    json!({
+        "type": "Message",
+        "_id": "01FVB1ZGCPS8TJ4PD4P7NAFDZD",
+        "channel": "01FVB1ZGCPS8TJ4PD4P7NAFDZE",
+        "author": "01FVB1ZGCPS8TJ4PD4P7NAFDZF",
+        "content": "Hello, world!"
+    })
+    .to_string()
+}
+
+fn decode(c: &mut Criterion) {
+    let all = EventFilter::all();
+    let messages = EventFilter::only(["Message"]);
+
+    let ready = ready();
+    assert!(matches!(
+        all.decode(Format::Json, FrameData::Text(ready.clone())),
+        Ok(Some(ServerToClientEvent::Ready { .. }))
+    ));
+    assert!(matches!(
+        messages.decode(Format::Json, FrameData::Text(ready.clone())),
+        Ok(None)
+    ));
+
+    let mut group = c.benchmark_group("ready");
+    group.sample_size(20);
+    group.bench_function("all", |b| {
+        b.iter(|| all.decode(Format::Json, FrameData::Text(black_box(ready.clone()))))
+    });
+    group.bench_function("filtered", |b| {
+        b.iter(|| messages.decode(Format::Json, FrameData::Text(black_box(ready.clone()))))
+    });
+    bench_serde(&mut group, &ready);
+    group.finish();
+
+    // Subscribed events pay for reading the tag first
+    let message = message();
+    let mut group = c.benchmark_group("message");
+    group.bench_function("all", |b| {
+        b.iter(|| all.decode(Format::Json, FrameData::Text(black_box(message.clone()))))
+    });
+    group.bench_function("filtered", |b| {
+        b.iter(|| messages.decode(Format::Json, FrameData::Text(black_box(message.clone()))))
+    });
+    bench_serde(&mut group, &message);
+    group.finish();
+}
+
+/// Plain serde for reference, without going through the gateway.
+///
+/// `known` is the derived implementation alone, `unknown` also falls back to
+/// [ServerToClientEvent::Unknown] and reads every event into a value first.
+fn bench_serde(group: &mut BenchmarkGroup<WallTime>, text: &str) {
+    group.bench_function("known", |b| {
+        b.iter(|| serde_json::from_str::<KnownEvent>(black_box(text)))
+    });
+    group.bench_function("unknown", |b| {
+        b.iter(|| serde_json::from_str::<ServerToClientEvent>(black_box(text)))
+    });
+}
+
+/// Decode every incoming frame of a session recorded with `RevoltWsBuilder::record`.
+///
+/// Only runs if `REVOLT_RECORDING` points at a recording, e.g.
+/// `REVOLT_RECORDING=session.jsonl cargo bench --bench decode`.
+fn recording(c: &mut Criterion) {
+    let path = match std::env::var("REVOLT_RECORDING") {
+        Ok(path) => path,
+        Err(_) => return,
+    };
+
+    let frames: Vec<(Format, FrameData)> = std::fs::read_to_string(path)
+        .expect("failed to read recording")
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str::<Frame>(line).expect("invalid frame"))
+        .filter(|frame| frame.direction == Direction::Incoming)
+        .map(|frame| match frame.data {
+            FrameData::Text(_) => (Format::Json, frame.data),
+            FrameData::Binary(_) => (Format::MessagePack, frame.data),
+        })
+        .collect();
+
+    let all = EventFilter::all();
+    let messages = EventFilter::only(["Message"]);
+
+    let mut group = c.benchmark_group("recording");
+    group.sample_size(10);
+    for (name, filter) in [("all", &all), ("filtered", &messages)] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for (format, data) in &frames {
+                    let _ = black_box(filter.decode(*format, data.clone()));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, decode, recording);
+criterion_main!(benches);
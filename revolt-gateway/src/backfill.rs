@@ -0,0 +1,394 @@
+use crate::GatewayError;
+use futures_util::{stream, StreamExt};
+use revolt_http::{RevoltHttp, RevoltHttpError};
+use revolt_models::{
+    message::{BulkMessageResponse, Message, MessageSort},
+    payload::FetchMessagesPayload,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::time::{timeout_at, Instant};
+
+/// Most messages the API returns per request.
+const PAGE_SIZE: usize = 100;
+
+/// Number of recent message ids kept per channel to recognize duplicates.
+const RECENT_IDS: usize = 200;
+
+/// Number of channels fetched from at the same time.
+const CONCURRENT_FETCHES: usize = 8;
+
+/// Fetches messages missed while the connection was down.
+///
+/// Every channel a `Message` event was received in is remembered together
+/// with the last message id. Once the connection is ready again after a
+/// reconnect, newer messages of those channels are fetched and yielded
+/// from the stream in order, before any live event that follows. Messages
+/// arriving both ways are only yielded once.
+///
+/// Enable with [crate::RevoltWsBuilder::backfill].
+#[derive(Clone)]
+pub struct Backfill {
+    http: Arc<RevoltHttp>,
+    max_messages: usize,
+    max_channels: usize,
+    deadline: Duration,
+    channels: HashMap<String, Cursor>,
+}
+
+/// What has been seen of a channel.
+#[derive(Debug, Clone, Default)]
+struct Cursor {
+    last: String,
+    recent: VecDeque<String>,
+}
+
+impl Backfill {
+    pub fn new(http: impl Into<Arc<RevoltHttp>>) -> Self {
+        Backfill {
+            http: http.into(),
+            max_messages: 500,
+            max_channels: 1000,
+            deadline: Duration::from_secs(5),
+            channels: HashMap::new(),
+        }
+    }
+
+    /// Most messages to fetch per channel after a reconnect, 500 by default.
+    ///
+    /// Missed messages are fetched oldest first, the most recent ones are left out if there are more.
+    pub fn max_messages(mut self, max_messages: usize) -> Self {
+        self.max_messages = max_messages;
+        self
+    }
+
+    /// Most channels to remember, 1000 by default.
+    ///
+    /// Once there are more, the channel that has been quiet the longest is forgotten.
+    pub fn max_channels(mut self, max_channels: usize) -> Self {
+        self.max_channels = max_channels.max(1);
+        self
+    }
+
+    /// How long fetching after a reconnect may take, 5 seconds by default.
+    ///
+    /// Nothing is read from the socket meanwhile, so this should stay well
+    /// below the time the server waits for a ping. Channels that are not
+    /// done by then are reported as [GatewayError::BackfillTimeout] and
+    /// fetched again on the next reconnect.
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Remember a message, returns `false` if it was seen before.
+    pub(crate) fn track(&mut self, message: &Message) -> bool {
+        if !self.channels.contains_key(&message.channel) && self.channels.len() >= self.max_channels
+        {
+            self.forget_quietest();
+        }
+
+        let cursor = self.channels.entry(message.channel.clone()).or_default();
+
+        if cursor.recent.contains(&message.id) {
+            return false;
+        }
+
+        if cursor.recent.len() == RECENT_IDS {
+            cursor.recent.pop_front();
+        }
+        cursor.recent.push_back(message.id.clone());
+
+        // Ids are ULIDs, they sort by creation time
+        if message.id > cursor.last {
+            cursor.last = message.id.clone();
+        }
+
+        true
+    }
+
+    /// Forget the channel with the oldest last message.
+    fn forget_quietest(&mut self) {
+        let quietest = self
+            .channels
+            .iter()
+            .min_by(|(_, a), (_, b)| a.last.cmp(&b.last))
+            .map(|(channel, _)| channel.clone());
+
+        if let Some(channel) = quietest {
+            self.channels.remove(&channel);
+        }
+    }
+
+    /// Fetch the messages after the last one seen in every channel, oldest first.
+    ///
+    /// Channels are fetched concurrently until the deadline. Those that fail
+    /// or are not done in time are skipped, their errors are returned next
+    /// to the messages.
+    pub(crate) async fn fetch_missed(&mut self) -> (Vec<Message>, Vec<GatewayError>) {
+        let mut fetched = Vec::new();
+        let mut errors = Vec::new();
+
+        let channels: Vec<(String, String)> = self
+            .channels
+            .iter()
+            .map(|(channel, cursor)| (channel.clone(), cursor.last.clone()))
+            .collect();
+        let mut remaining = channels.len();
+
+        let deadline = Instant::now() + self.deadline;
+        let mut fetches = stream::iter(channels)
+            .map(|(channel, after)| fetch_channel(&self.http, channel, after, self.max_messages))
+            .buffer_unordered(CONCURRENT_FETCHES);
+
+        loop {
+            match timeout_at(deadline, fetches.next()).await {
+                Ok(Some((messages, error))) => {
+                    remaining -= 1;
+                    fetched.extend(messages);
+                    errors.extend(error.map(GatewayError::from));
+                }
+                Ok(None) => break,
+                Err(_) => {
+                    errors.push(GatewayError::BackfillTimeout {
+                        channels: remaining,
+                    });
+                    break;
+                }
+            }
+        }
+        drop(fetches);
+
+        // Interleave channels in the order messages were sent
+        fetched.sort_by(|a, b| a.id.cmp(&b.id));
+        fetched.retain(|message| self.track(message));
+
+        (fetched, errors)
+    }
+}
+
+/// Fetch up to `max_messages` messages of a channel sent after `after`, oldest first.
+///
+/// Messages fetched before an error are returned with it.
+async fn fetch_channel(
+    http: &RevoltHttp,
+    channel: String,
+    mut after: String,
+    max_messages: usize,
+) -> (Vec<Message>, Option<RevoltHttpError>) {
+    let mut fetched = Vec::new();
+
+    while fetched.len() < max_messages {
+        let limit = PAGE_SIZE.min(max_messages - fetched.len());
+        let payload = FetchMessagesPayload {
+            limit: Some(limit as i64),
+            before: None,
+            after: Some(after.clone()),
+            sort: Some(MessageSort::Oldest),
+            nearby: None,
+            include_users: None,
+        };
+
+        let messages = match http.fetch_messages(&channel, payload).await {
+            Ok(BulkMessageResponse::JustMessages(messages)) => messages,
+            Ok(BulkMessageResponse::MessagesAndUsers { messages, .. }) => messages,
+            Err(err) => return (fetched, Some(err)),
+        };
+
+        let count = messages.len();
+        if let Some(last) = messages.last() {
+            after = last.id.clone();
+        }
+        fetched.extend(messages);
+
+        if count < limit {
+            break;
+        }
+    }
+
+    (fetched, None)
+}
+
+impl fmt::Debug for Backfill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Backfill")
+            .field("max_messages", &self.max_messages)
+            .field("max_channels", &self.max_channels)
+            .field("deadline", &self.deadline)
+            .field("channels", &self.channels.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::{Request, Response};
+    use revolt_http::{Transport, TransportError};
+    use revolt_models::authentication::Authentication;
+    use serde_json::{json, Value};
+    use std::sync::Mutex;
+
+    /// Answers message fetches from a fixed history per channel.
+    #[derive(Debug, Default)]
+    struct History {
+        channels: HashMap<&'static str, Vec<String>>,
+        /// Channel whose requests never get a response
+        stalled: Option<&'static str>,
+        requests: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Transport for History {
+        async fn send(&self, request: Request) -> Result<Response, TransportError> {
+            // `/channels/{id}/messages`
+            let channel = request.url().path().split('/').nth(2).unwrap().to_string();
+            self.requests.lock().unwrap().push(channel.clone());
+
+            if self.stalled == Some(channel.as_str()) {
+                std::future::pending::<()>().await;
+            }
+
+            let query: HashMap<String, String> = request.url().query_pairs().into_owned().collect();
+            let limit: usize = query["limit"].parse().unwrap();
+            let messages: Vec<Value> = self.channels[channel.as_str()]
+                .iter()
+                .filter(|id| **id > query["after"])
+                .take(limit)
+                .map(|id| message_json(&channel, id))
+                .collect();
+
+            let response = http::Response::builder()
+                .status(200)
+                .body(Value::from(messages).to_string())
+                .unwrap();
+            Ok(Response::from(response))
+        }
+    }
+
+    fn message_json(channel: &str, id: &str) -> Value {
+        json!({ "_id": id, "channel": channel, "author": "user" })
+    }
+
+    fn message(channel: &str, id: &str) -> Message {
+        serde_json::from_value(message_json(channel, id)).unwrap()
+    }
+
+    fn backfill(history: History) -> (Backfill, Arc<History>) {
+        let history = Arc::new(history);
+        let http = RevoltHttp::builder(Authentication::BotToken("token".to_string()))
+            .transport(history.clone())
+            .build()
+            .unwrap();
+        (Backfill::new(http), history)
+    }
+
+    fn history(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    fn ids(messages: &[Message]) -> Vec<&str> {
+        messages.iter().map(|message| message.id.as_str()).collect()
+    }
+
+    #[test]
+    fn track_skips_seen_messages() {
+        let (mut backfill, _) = backfill(History::default());
+
+        assert!(backfill.track(&message("a", "01")));
+        assert!(!backfill.track(&message("a", "01")));
+        assert!(backfill.track(&message("a", "02")));
+        // Same id in another channel is another message
+        assert!(backfill.track(&message("b", "01")));
+
+        // Older message arriving late doesn't move the cursor back
+        assert!(backfill.track(&message("a", "00")));
+        assert_eq!(backfill.channels["a"].last, "02");
+    }
+
+    #[test]
+    fn track_forgets_quietest_channel() {
+        let (backfill, _) = backfill(History::default());
+        let mut backfill = backfill.max_channels(2);
+
+        backfill.track(&message("a", "01"));
+        backfill.track(&message("b", "02"));
+        backfill.track(&message("a", "03"));
+        backfill.track(&message("c", "04"));
+
+        let mut channels: Vec<&str> = backfill.channels.keys().map(String::as_str).collect();
+        channels.sort();
+        assert_eq!(channels, ["a", "c"]);
+    }
+
+    #[tokio::test]
+    async fn fetch_missed_yields_messages_in_order() {
+        let (mut backfill, _) = backfill(History {
+            channels: HashMap::from([
+                ("a", history(&["01", "03", "05", "07"])),
+                ("b", history(&["02", "04", "06"])),
+            ]),
+            ..Default::default()
+        });
+
+        backfill.track(&message("a", "01"));
+        backfill.track(&message("b", "02"));
+        // Received live before the fetch finished
+        backfill.track(&message("b", "04"));
+
+        let (missed, errors) = backfill.fetch_missed().await;
+        assert!(errors.is_empty());
+        assert_eq!(ids(&missed), ["03", "05", "06", "07"]);
+
+        // Nothing new the second time
+        let (missed, _) = backfill.fetch_missed().await;
+        assert!(missed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn fetch_missed_pages_up_to_max_messages() {
+        let history = (0..250).map(|i| format!("{i:03}")).collect();
+        let (backfill, requests) = backfill(History {
+            channels: HashMap::from([("a", history)]),
+            ..Default::default()
+        });
+        let mut backfill = backfill.max_messages(150);
+
+        backfill.track(&message("a", "000"));
+
+        let (missed, _) = backfill.fetch_missed().await;
+        assert_eq!(missed.len(), 150);
+        assert_eq!(missed.first().unwrap().id, "001");
+        assert_eq!(missed.last().unwrap().id, "150");
+        assert_eq!(requests.requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fetch_missed_stops_at_deadline() {
+        let (backfill, _) = backfill(History {
+            channels: HashMap::from([("a", history(&["01", "03"])), ("b", history(&["02"]))]),
+            stalled: Some("b"),
+            ..Default::default()
+        });
+        let mut backfill = backfill.deadline(Duration::from_secs(1));
+
+        backfill.track(&message("a", "01"));
+        backfill.track(&message("b", "02"));
+
+        let started = Instant::now();
+        let (missed, errors) = backfill.fetch_missed().await;
+        assert_eq!(started.elapsed(), Duration::from_secs(1));
+        assert_eq!(ids(&missed), ["03"]);
+        assert!(matches!(
+            errors[..],
+            [GatewayError::BackfillTimeout { channels: 1 }]
+        ));
+
+        // Stalled channel is still remembered for the next reconnect
+        assert_eq!(backfill.channels["b"].last, "02");
+    }
+}
@@ -0,0 +1,90 @@
+use rand::Rng;
+use std::time::Duration;
+
+/// Exponential backoff with full jitter used between reconnect attempts.
+#[derive(Debug, Clone)]
+pub(crate) struct Backoff {
+    initial: Duration,
+    max: Duration,
+    attempt: u32,
+}
+
+impl Backoff {
+    pub(crate) fn new(initial: Duration, max: Duration) -> Self {
+        Backoff {
+            initial,
+            max,
+            attempt: 0,
+        }
+    }
+
+    /// Number of delays handed out since the last reset.
+    pub(crate) fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Delay to wait before the next attempt.
+    ///
+    /// The upper bound doubles on every call until it reaches `max`,
+    /// the actual delay is picked uniformly between zero and that bound.
+    pub(crate) fn next_delay(&mut self) -> Duration {
+        let ceiling = self
+            .initial
+            .saturating_mul(2u32.saturating_pow(self.attempt.min(16)))
+            .min(self.max);
+        self.attempt = self.attempt.saturating_add(1);
+
+        rand::thread_rng().gen_range(Duration::ZERO..=ceiling)
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INITIAL: Duration = Duration::from_millis(100);
+    const MAX: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn delays_stay_below_the_doubling_ceiling() {
+        let mut backoff = Backoff::new(INITIAL, MAX);
+        let ceilings = [100, 200, 400, 800, 1000, 1000].map(Duration::from_millis);
+
+        for (attempt, ceiling) in ceilings.into_iter().enumerate() {
+            assert_eq!(backoff.attempt(), attempt as u32);
+            for _ in 0..50 {
+                let mut probe = backoff.clone();
+                assert!(probe.next_delay() <= ceiling);
+            }
+            backoff.next_delay();
+        }
+    }
+
+    #[test]
+    fn ceiling_does_not_overflow() {
+        let mut backoff = Backoff::new(INITIAL, MAX);
+        for _ in 0..100 {
+            assert!(backoff.next_delay() <= MAX);
+        }
+        assert_eq!(backoff.attempt(), 100);
+    }
+
+    #[test]
+    fn reset_starts_over() {
+        let mut backoff = Backoff::new(INITIAL, MAX);
+        for _ in 0..5 {
+            backoff.next_delay();
+        }
+
+        backoff.reset();
+        assert_eq!(backoff.attempt(), 0);
+        for _ in 0..50 {
+            let mut probe = backoff.clone();
+            assert!(probe.next_delay() <= INITIAL);
+        }
+    }
+}
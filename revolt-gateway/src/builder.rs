@@ -0,0 +1,349 @@
+#[cfg(feature = "backfill")]
+use crate::backfill::Backfill;
+use crate::{
+    backoff::Backoff, connection::Config, encoding::Format, filter::EventFilter,
+    queue::OverflowPolicy, record::Recorder, GatewayError, RevoltWs,
+};
+use std::{fmt, path::PathBuf, time::Duration};
+use tokio_tungstenite::{
+    tungstenite::{
+        http::{HeaderName, HeaderValue},
+        protocol::WebSocketConfig,
+    },
+    Connector,
+};
+use url::Url;
+
+/// Shortest interval between pings, a zero interval would never let the connection rest.
+const MIN_HEARTBEAT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Builder for a [RevoltWs] connection.
+#[derive(Clone)]
+pub struct RevoltWsBuilder {
+    url: String,
+    token: String,
+    version: Option<u32>,
+    format: Option<Format>,
+    token_in_url: bool,
+    flatten_bulk: bool,
+    events: EventFilter,
+    incoming_capacity: usize,
+    outgoing_capacity: usize,
+    overflow_policy: OverflowPolicy,
+    heartbeat_interval: Duration,
+    heartbeat_timeout: Duration,
+    reconnect_delay: (Duration, Duration),
+    websocket_config: Option<WebSocketConfig>,
+    connector: Option<Connector>,
+    headers: Vec<(String, String)>,
+    record: Option<PathBuf>,
+    #[cfg(feature = "backfill")]
+    backfill: Option<Backfill>,
+}
+
+impl RevoltWsBuilder {
+    pub fn new(token: impl Into<String>) -> Self {
+        RevoltWsBuilder {
+            url: "wss://ws.revolt.chat".to_string(),
+            token: token.into(),
+            version: None,
+            format: None,
+            token_in_url: false,
+            flatten_bulk: true,
+            events: EventFilter::all(),
+            incoming_capacity: 1024,
+            outgoing_capacity: 64,
+            overflow_policy: OverflowPolicy::default(),
+            heartbeat_interval: Duration::from_secs(15),
+            heartbeat_timeout: Duration::from_secs(10),
+            reconnect_delay: (Duration::from_secs(1), Duration::from_secs(60)),
+            websocket_config: None,
+            connector: None,
+            headers: Vec::new(),
+            record: None,
+            #[cfg(feature = "backfill")]
+            backfill: None,
+        }
+    }
+
+    /// Events server URL, `wss://ws.revolt.chat` by default.
+    ///
+    /// If no [RevoltWsBuilder::format] is set, the format is picked
+    /// from the `format` query parameter of this URL.
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Protocol version to request through the `version` query parameter.
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Wire format to request through the `format` query parameter.
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Pass the token through the `token` query parameter instead of
+    /// sending an `Authenticate` event after connecting.
+    pub fn token_in_url(mut self, token_in_url: bool) -> Self {
+        self.token_in_url = token_in_url;
+        self
+    }
+
+    /// Whether to unpack `Bulk` events, recursively and in order,
+    /// so the stream only yields concrete events. Enabled by default.
+    pub fn flatten_bulk(mut self, flatten_bulk: bool) -> Self {
+        self.flatten_bulk = flatten_bulk;
+        self
+    }
+
+    /// Which events to decode and yield from the stream, all of them by default.
+    ///
+    /// Skipping events a bot does not handle saves parsing their payloads.
+    pub fn events(mut self, filter: EventFilter) -> Self {
+        self.events = filter;
+        self
+    }
+
+    /// Maximum number of received events waiting to be read from the stream,
+    /// 1024 by default.
+    pub fn incoming_capacity(mut self, capacity: usize) -> Self {
+        self.incoming_capacity = capacity;
+        self
+    }
+
+    /// Maximum number of events waiting to be sent, 64 by default.
+    ///
+    /// [RevoltWs::send] waits for space once it is reached.
+    pub fn outgoing_capacity(mut self, capacity: usize) -> Self {
+        self.outgoing_capacity = capacity;
+        self
+    }
+
+    /// What to do when the incoming queue is full, [OverflowPolicy::Block] by default.
+    pub fn overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow_policy = policy;
+        self
+    }
+
+    /// How often to ping the server, 15 seconds by default.
+    ///
+    /// Intervals below 100 milliseconds are raised to that.
+    pub fn heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.heartbeat_interval = interval;
+        self
+    }
+
+    /// How long to wait for a pong before considering the connection dead,
+    /// 10 seconds by default.
+    pub fn heartbeat_timeout(mut self, timeout: Duration) -> Self {
+        self.heartbeat_timeout = timeout;
+        self
+    }
+
+    /// Bounds for the randomized exponential delay between reconnect attempts,
+    /// 1 and 60 seconds by default.
+    pub fn reconnect_delay(mut self, initial: Duration, max: Duration) -> Self {
+        self.reconnect_delay = (initial, max);
+        self
+    }
+
+    /// Socket buffer and message size limits.
+    pub fn websocket_config(mut self, config: WebSocketConfig) -> Self {
+        self.websocket_config = Some(config);
+        self
+    }
+
+    /// Custom TLS connector, e.g. with additional root certificates.
+    pub fn connector(mut self, connector: Connector) -> Self {
+        self.connector = Some(connector);
+        self
+    }
+
+    /// Additional header to send with the handshake request.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Record every frame sent and received to a JSONL file, see [crate::ReplayWs].
+    ///
+    /// Frames are appended if the file exists. The token is redacted from the recording.
+    pub fn record(mut self, path: impl Into<PathBuf>) -> Self {
+        self.record = Some(path.into());
+        self
+    }
+
+    /// Fetch messages missed while reconnecting and yield them from the stream.
+    #[cfg(feature = "backfill")]
+    pub fn backfill(mut self, backfill: Backfill) -> Self {
+        self.backfill = Some(backfill);
+        self
+    }
+
+    pub async fn connect(self) -> Result<RevoltWs, GatewayError> {
+        let recorder = match &self.record {
+            Some(path) => Some(Recorder::create(path).await?),
+            None => None,
+        };
+
+        RevoltWs::connect_with_config(self.build_config(recorder)?).await
+    }
+
+    fn build_config(self, recorder: Option<Recorder>) -> Result<Config, GatewayError> {
+        let mut params = Vec::new();
+        if let Some(version) = self.version {
+            params.push(("version", version.to_string()));
+        }
+        if let Some(format) = self.format {
+            params.push(("format", format.query_value().to_string()));
+        }
+        if self.token_in_url {
+            params.push(("token", self.token.clone()));
+        }
+
+        let url = if params.is_empty() {
+            self.url
+        } else {
+            // Values set on the builder replace those already in the URL
+            let mut url = Url::parse(&self.url)?;
+            let kept: Vec<(String, String)> = url
+                .query_pairs()
+                .into_owned()
+                .filter(|(key, _)| params.iter().all(|(name, _)| key != name))
+                .collect();
+            url.query_pairs_mut()
+                .clear()
+                .extend_pairs(kept)
+                .extend_pairs(params);
+            url.into()
+        };
+
+        let headers = self
+            .headers
+            .into_iter()
+            .map(|(name, value)| {
+                Ok((
+                    HeaderName::try_from(name).map_err(http_error)?,
+                    HeaderValue::try_from(value).map_err(http_error)?,
+                ))
+            })
+            .collect::<Result<_, GatewayError>>()?;
+
+        let (initial, max) = self.reconnect_delay;
+
+        Ok(Config {
+            format: self.format.unwrap_or_else(|| Format::from_url(&url)),
+            url,
+            token: self.token,
+            authenticate: !self.token_in_url,
+            flatten_bulk: self.flatten_bulk,
+            events: self.events,
+            incoming_capacity: self.incoming_capacity,
+            outgoing_capacity: self.outgoing_capacity.max(1),
+            overflow_policy: self.overflow_policy,
+            heartbeat_interval: self.heartbeat_interval.max(MIN_HEARTBEAT_INTERVAL),
+            heartbeat_timeout: self.heartbeat_timeout,
+            backoff: Backoff::new(initial, max),
+            websocket_config: self.websocket_config,
+            connector: self.connector,
+            headers,
+            recorder,
+            #[cfg(feature = "backfill")]
+            backfill: self.backfill,
+        })
+    }
+}
+
+fn http_error(err: impl Into<tokio_tungstenite::tungstenite::http::Error>) -> GatewayError {
+    GatewayError::from(tokio_tungstenite::tungstenite::Error::from(err.into()))
+}
+
+impl fmt::Debug for RevoltWsBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Token is left out on purpose, connector is not Debug
+        let mut debug = f.debug_struct("RevoltWsBuilder");
+        debug
+            .field("url", &self.url)
+            .field("version", &self.version)
+            .field("format", &self.format)
+            .field("token_in_url", &self.token_in_url)
+            .field("flatten_bulk", &self.flatten_bulk)
+            .field("events", &self.events)
+            .field("incoming_capacity", &self.incoming_capacity)
+            .field("outgoing_capacity", &self.outgoing_capacity)
+            .field("overflow_policy", &self.overflow_policy)
+            .field("heartbeat_interval", &self.heartbeat_interval)
+            .field("heartbeat_timeout", &self.heartbeat_timeout)
+            .field("reconnect_delay", &self.reconnect_delay)
+            .field("websocket_config", &self.websocket_config)
+            .field("headers", &self.headers)
+            .field("record", &self.record);
+
+        #[cfg(feature = "backfill")]
+        debug.field("backfill", &self.backfill);
+
+        debug.finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(builder: RevoltWsBuilder) -> Config {
+        builder.build_config(None).unwrap()
+    }
+
+    #[test]
+    fn url_is_left_alone_without_parameters() {
+        let config = config(RevoltWsBuilder::new("token").url("wss://example.com?format=msgpack"));
+        assert_eq!(config.url, "wss://example.com?format=msgpack");
+        assert_eq!(config.format, Format::MessagePack);
+    }
+
+    #[test]
+    fn parameters_replace_those_in_url() {
+        let config = config(
+            RevoltWsBuilder::new("token")
+                .url("wss://example.com/?version=0&format=msgpack&shard=2")
+                .version(1)
+                .format(Format::Json),
+        );
+        assert_eq!(
+            config.url,
+            "wss://example.com/?shard=2&version=1&format=json"
+        );
+        assert_eq!(config.format, Format::Json);
+    }
+
+    #[test]
+    fn token_in_url_is_encoded() {
+        let config = config(
+            RevoltWsBuilder::new("a+b/c&d=e")
+                .url("wss://example.com?token=old")
+                .token_in_url(true),
+        );
+        assert_eq!(config.url, "wss://example.com/?token=a%2Bb%2Fc%26d%3De");
+        assert!(!config.authenticate);
+    }
+
+    #[test]
+    fn invalid_url_is_rejected() {
+        let result = RevoltWsBuilder::new("token")
+            .url("not a url")
+            .version(1)
+            .build_config(None);
+        assert!(matches!(result, Err(GatewayError::UrlError(_))));
+    }
+
+    #[test]
+    fn zero_heartbeat_interval_is_raised() {
+        let config = config(RevoltWsBuilder::new("token").heartbeat_interval(Duration::ZERO));
+        assert_eq!(config.heartbeat_interval, MIN_HEARTBEAT_INTERVAL);
+    }
+}
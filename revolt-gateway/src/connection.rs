@@ -0,0 +1,533 @@
+use crate::{
+    backoff::Backoff,
+    encoding::Format,
+    filter::EventFilter,
+    heartbeat::Heartbeat,
+    queue::{EventSender, OverflowPolicy},
+    record::{Direction, FrameData, Recorder},
+    state::{self, CloseReason, ConnectionState, Transition},
+    GatewayError,
+};
+use futures_util::{
+    future,
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
+use revolt_models::event::{ClientToServerEvent, ServerToClientEvent};
+use std::{fmt, time::Duration};
+use tokio::{
+    net::TcpStream,
+    select,
+    sync::{broadcast, mpsc::Receiver, oneshot, watch},
+    time::{interval, sleep, sleep_until, timeout},
+};
+use tokio_tungstenite::{
+    tungstenite::{
+        self,
+        client::IntoClientRequest,
+        http::{HeaderName, HeaderValue},
+        protocol::{frame::coding::CloseCode, CloseFrame, WebSocketConfig},
+        Message,
+    },
+    Connector, MaybeTlsStream, WebSocketStream,
+};
+
+pub(crate) type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type SocketWriter = SplitSink<Socket, Message>;
+type SocketReader = SplitStream<Socket>;
+
+/// How long to wait for the server to acknowledge a close frame.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Notifications about the underlying connection.
+///
+/// These are delivered separately from server events, see [crate::RevoltWs::connection_events].
+#[derive(Debug, Clone)]
+pub enum ConnectionEvent {
+    /// Connection was lost, reconnection is about to start
+    Disconnected { reason: String },
+    /// Waiting before the next reconnect attempt
+    ///
+    /// Attempts are counted since the connection was last ready, so sessions
+    /// that drop before getting ready make the delays keep growing.
+    Reconnecting { attempt: u32, delay: Duration },
+    /// Reconnect attempt failed, another one will follow
+    ReconnectFailed { attempt: u32, reason: String },
+    /// Connection is restored and authentication has been sent again
+    ///
+    /// Sent as soon as the socket is open if the token is part of the URL.
+    Reconnected { attempts: u32 },
+}
+
+/// Everything needed to (re)establish a session.
+#[derive(Clone)]
+pub(crate) struct Config {
+    /// Full URL including query parameters
+    pub(crate) url: String,
+    pub(crate) token: String,
+    /// Whether to send an `Authenticate` event, the token may be part of the URL instead
+    pub(crate) authenticate: bool,
+    pub(crate) format: Format,
+    /// Whether to unpack `Bulk` events into the stream
+    pub(crate) flatten_bulk: bool,
+    /// Events to decode, others are dropped after reading their type
+    pub(crate) events: EventFilter,
+    pub(crate) incoming_capacity: usize,
+    pub(crate) outgoing_capacity: usize,
+    pub(crate) overflow_policy: OverflowPolicy,
+    pub(crate) heartbeat_interval: Duration,
+    /// Connection is considered dead if a ping is not answered within this time
+    pub(crate) heartbeat_timeout: Duration,
+    pub(crate) backoff: Backoff,
+    pub(crate) websocket_config: Option<WebSocketConfig>,
+    pub(crate) connector: Option<Connector>,
+    pub(crate) headers: Vec<(HeaderName, HeaderValue)>,
+    pub(crate) recorder: Option<Recorder>,
+    #[cfg(feature = "backfill")]
+    pub(crate) backfill: Option<crate::backfill::Backfill>,
+}
+
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // URL may contain the token, connector is not Debug
+        f.debug_struct("Config")
+            .field("format", &self.format)
+            .field("events", &self.events)
+            .field("heartbeat_interval", &self.heartbeat_interval)
+            .field("heartbeat_timeout", &self.heartbeat_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+pub(crate) async fn connect_socket(config: &Config) -> Result<Socket, GatewayError> {
+    let mut request = config.url.as_str().into_client_request()?;
+    request.headers_mut().extend(config.headers.iter().cloned());
+
+    let (ws, _) = tokio_tungstenite::connect_async_tls_with_config(
+        request,
+        config.websocket_config,
+        config.connector.clone(),
+    )
+    .await?;
+    Ok(ws)
+}
+
+enum SessionEnd {
+    /// Socket was lost and should be reestablished
+    Disconnected(String),
+    /// [crate::RevoltWs] was closed or dropped, nobody is listening anymore
+    Shutdown,
+    /// Server will not accept this connection
+    Terminated(CloseReason),
+}
+
+/// Owns the socket and keeps it alive across reconnects.
+pub(crate) struct Driver {
+    pub(crate) config: Config,
+    pub(crate) server_sender: EventSender,
+    pub(crate) client_receiver: Receiver<ClientToServerEvent>,
+    pub(crate) connection_events: broadcast::Sender<ConnectionEvent>,
+    pub(crate) heartbeat: Heartbeat,
+    pub(crate) state: watch::Sender<ConnectionState>,
+    /// Fires on [crate::RevoltWs::close] or when the sending half is dropped
+    pub(crate) close_receiver: oneshot::Receiver<()>,
+    /// Event taken from the queue whose write failed, sent again after reconnect
+    pub(crate) pending: Option<ClientToServerEvent>,
+    /// Attempts of the last reconnect, reported once the session is set up again
+    pub(crate) reconnected: Option<u32>,
+}
+
+impl Driver {
+    /// Returns the config once the connection is closed, so the recorder
+    /// and backfill state can be taken over by a new connection.
+    pub(crate) async fn run(mut self, mut ws: Socket) -> Config {
+        loop {
+            match self.run_session(ws).await {
+                SessionEnd::Shutdown => break,
+                SessionEnd::Terminated(reason) => {
+                    self.set_state(ConnectionState::Closed { reason });
+                    return self.config;
+                }
+                SessionEnd::Disconnected(reason) => {
+                    self.emit(ConnectionEvent::Disconnected { reason });
+                }
+            }
+
+            ws = match self.reconnect().await {
+                Some(ws) => ws,
+                None => break,
+            };
+        }
+
+        self.set_state(ConnectionState::Closed {
+            reason: CloseReason::Requested,
+        });
+        self.config
+    }
+
+    fn set_state(&self, state: ConnectionState) {
+        self.state.send_replace(state);
+    }
+
+    fn emit(&self, event: ConnectionEvent) {
+        // Nobody subscribed is not an error
+        let _ = self.connection_events.send(event);
+    }
+
+    /// Forward an error to the event stream.
+    ///
+    /// Returns `false` if the stream is gone.
+    fn report(&self, err: GatewayError) -> bool {
+        self.server_sender.force_send(Err(err)).is_ok()
+    }
+
+    async fn reconnect(&mut self) -> Option<Socket> {
+        // Only a session that got ready starts the delays over, one dropped
+        // right after connecting keeps backing off
+        if *self.state.borrow() == ConnectionState::Ready {
+            self.config.backoff.reset();
+        }
+
+        loop {
+            let delay = self.config.backoff.next_delay();
+            let attempt = self.config.backoff.attempt();
+            self.emit(ConnectionEvent::Reconnecting { attempt, delay });
+            self.set_state(ConnectionState::Reconnecting { attempt });
+
+            select! {
+                _ = sleep(delay) => {}
+                _ = &mut self.close_receiver => return None,
+            }
+
+            self.set_state(ConnectionState::Connecting);
+
+            let result = select! {
+                result = connect_socket(&self.config) => result,
+                _ = &mut self.close_receiver => return None,
+            };
+
+            match result {
+                Ok(ws) => {
+                    self.reconnected = Some(attempt);
+                    return Some(ws);
+                }
+                Err(err) => self.emit(ConnectionEvent::ReconnectFailed {
+                    attempt,
+                    reason: err.to_string(),
+                }),
+            }
+        }
+    }
+
+    async fn run_session(&mut self, ws: Socket) -> SessionEnd {
+        let (mut ws_writer, mut ws_reader) = ws.split();
+        self.set_state(ConnectionState::Authenticating);
+
+        if self.config.authenticate {
+            let authenticate = ClientToServerEvent::Authenticate {
+                token: self.config.token.clone(),
+            };
+            if let Err(err) = self.send_event(&mut ws_writer, &authenticate).await {
+                return SessionEnd::Disconnected(err.to_string());
+            }
+        }
+        if let Some(attempts) = self.reconnected.take() {
+            self.emit(ConnectionEvent::Reconnected { attempts });
+        }
+
+        if let Some(event) = self.pending.take() {
+            if let Err(err) = self.send_event(&mut ws_writer, &event).await {
+                self.pending = Some(event);
+                return SessionEnd::Disconnected(err.to_string());
+            }
+        }
+
+        self.heartbeat.reset();
+        let mut heartbeat = interval(self.config.heartbeat_interval);
+
+        loop {
+            let pong_deadline = self.heartbeat.deadline(self.config.heartbeat_timeout);
+
+            select! {
+                // Receive message -> decode -> send to channel
+                msg = ws_reader.next() => {
+                    if let (Some(recorder), Some(Ok(msg))) = (&self.config.recorder, &msg) {
+                        recorder.record(Direction::Incoming, msg);
+                    }
+
+                    let data = match msg {
+                        Some(Ok(Message::Text(text))) => FrameData::Text(text),
+                        Some(Ok(Message::Binary(bytes))) => FrameData::Binary(bytes),
+                        Some(Ok(Message::Close(frame))) => {
+                            let reason = match frame {
+                                Some(frame) => format!("closed by server: {}", frame.reason),
+                                None => "closed by server".to_string(),
+                            };
+                            return SessionEnd::Disconnected(reason);
+                        }
+                        Some(Ok(_)) => continue,
+                        Some(Err(err)) => return SessionEnd::Disconnected(err.to_string()),
+                        None => return SessionEnd::Disconnected("connection closed".to_string()),
+                    };
+
+                    #[cfg(feature = "backfill")]
+                    let was_ready = *self.state.borrow() == ConnectionState::Ready;
+
+                    let mut skipped = Vec::new();
+                    let event = self.config.events.decode_frame(self.config.format, data, &mut skipped);
+                    self.observe_skipped(&skipped);
+
+                    let event = match event.transpose() {
+                        Some(event) => event,
+                        None => continue,
+                    };
+
+                    let mut events = Vec::new();
+                    match event {
+                        Ok(ServerToClientEvent::Bulk { v }) if self.config.flatten_bulk => {
+                            flatten_bulk(v, &mut events);
+                        }
+                        event => events.push(event),
+                    }
+
+                    for event in events {
+                        #[cfg(feature = "backfill")]
+                        if let (Some(backfill), Ok(ServerToClientEvent::Message { message })) =
+                            (&mut self.config.backfill, &event)
+                        {
+                            // Already yielded by a backfill
+                            if !backfill.track(message) {
+                                continue;
+                            }
+                        }
+
+                        let transition = match &event {
+                            Ok(event) => self.observe(event),
+                            Err(_) => Transition::Continue(None),
+                        };
+
+                        // Queue may apply backpressure, closing has to stay possible meanwhile
+                        let sent = select! {
+                            result = self.server_sender.send(event) => result.is_ok(),
+                            _ = &mut self.close_receiver => false,
+                        };
+                        if !sent {
+                            self.shutdown(ws_writer, ws_reader).await;
+                            return SessionEnd::Shutdown;
+                        }
+
+                        match transition {
+                            Transition::Continue(Some(state)) => self.set_state(state),
+                            Transition::Continue(None) => {}
+                            Transition::Reconnect(reason) => {
+                                return SessionEnd::Disconnected(reason);
+                            }
+                            Transition::Terminate(reason) => {
+                                self.shutdown(ws_writer, ws_reader).await;
+                                return SessionEnd::Terminated(reason);
+                            }
+                        }
+                    }
+
+                    #[cfg(feature = "backfill")]
+                    {
+                        let became_ready = !was_ready && *self.state.borrow() == ConnectionState::Ready;
+                        if became_ready && !self.backfill().await {
+                            self.shutdown(ws_writer, ws_reader).await;
+                            return SessionEnd::Shutdown;
+                        }
+                    }
+                }
+
+                // Receive from channel -> encode -> send message
+                event = self.client_receiver.recv() => {
+                    let event = match event {
+                        Some(event) => event,
+                        None => {
+                            self.shutdown(ws_writer, ws_reader).await;
+                            return SessionEnd::Shutdown;
+                        }
+                    };
+
+                    if let Err(err) = self.send_event(&mut ws_writer, &event).await {
+                        self.pending = Some(event);
+                        return SessionEnd::Disconnected(err.to_string());
+                    }
+                }
+
+                // Send heartbeat periodically
+                _ = heartbeat.tick() => {
+                    let ping = self.heartbeat.ping();
+                    if let Err(err) = self.send_event(&mut ws_writer, &ping).await {
+                        return SessionEnd::Disconnected(err.to_string());
+                    }
+                }
+
+                // Give up on a connection that stopped answering pings
+                _ = async {
+                    match pong_deadline {
+                        Some(deadline) => sleep_until(deadline).await,
+                        None => future::pending().await,
+                    }
+                } => {
+                    return SessionEnd::Disconnected("heartbeat timed out".to_string());
+                }
+
+                // Close requested or the connection handle was dropped
+                _ = &mut self.close_receiver => {
+                    self.shutdown(ws_writer, ws_reader).await;
+                    return SessionEnd::Shutdown;
+                }
+            }
+        }
+    }
+
+    /// Update heartbeat and state bookkeeping for a received event.
+    fn observe(&mut self, event: &ServerToClientEvent) -> Transition {
+        match event {
+            ServerToClientEvent::Pong { data } => {
+                self.heartbeat.pong(*data);
+                Transition::Continue(None)
+            }
+            ServerToClientEvent::Bulk { v } => {
+                for event in v {
+                    match self.observe(event) {
+                        Transition::Continue(Some(state)) => self.set_state(state),
+                        Transition::Continue(None) => {}
+                        transition => return transition,
+                    }
+                }
+                Transition::Continue(None)
+            }
+            event => state::transition(&self.state.borrow(), event),
+        }
+    }
+
+    /// Yield messages missed since the last session.
+    ///
+    /// Returns `false` if the stream is gone or closing was requested meanwhile.
+    #[cfg(feature = "backfill")]
+    async fn backfill(&mut self) -> bool {
+        let backfill = match &mut self.config.backfill {
+            Some(backfill) => backfill,
+            None => return true,
+        };
+
+        let (missed, errors) = select! {
+            result = backfill.fetch_missed() => result,
+            _ = &mut self.close_receiver => return false,
+        };
+
+        for err in errors {
+            if !self.report(err) {
+                return false;
+            }
+        }
+
+        for message in missed {
+            let event = Ok(ServerToClientEvent::Message { message });
+            let sent = select! {
+                result = self.server_sender.send(event) => result.is_ok(),
+                _ = &mut self.close_receiver => false,
+            };
+            if !sent {
+                return false;
+            }
+        }
+
+        // Pongs piled up unread while fetching, don't mistake that for a dead connection
+        self.heartbeat.reset();
+        true
+    }
+
+    /// Update the state for events that were filtered out before decoding.
+    fn observe_skipped(&self, event_types: &[String]) {
+        if event_types.iter().any(|event_type| event_type == "Ready") {
+            self.set_state(ConnectionState::Ready);
+        }
+    }
+
+    /// Encode and write a single event.
+    ///
+    /// Encoding failures are reported on the event stream,
+    /// only socket failures are returned.
+    async fn send_event(
+        &self,
+        ws_writer: &mut SocketWriter,
+        event: &ClientToServerEvent,
+    ) -> Result<(), tungstenite::Error> {
+        let msg = match self.config.format.encode(event) {
+            Ok(msg) => msg,
+            Err(err) => {
+                self.report(err);
+                return Ok(());
+            }
+        };
+
+        if let Some(recorder) = &self.config.recorder {
+            match event {
+                // Keep the token out of recordings
+                ClientToServerEvent::Authenticate { .. } => {
+                    let redacted = ClientToServerEvent::Authenticate {
+                        token: "<redacted>".to_string(),
+                    };
+                    if let Ok(msg) = self.config.format.encode(&redacted) {
+                        recorder.record(Direction::Outgoing, &msg);
+                    }
+                }
+                _ => recorder.record(Direction::Outgoing, &msg),
+            }
+        }
+
+        ws_writer.send(msg).await
+    }
+
+    /// Flush queued events and perform the closing handshake.
+    async fn shutdown(&mut self, mut ws_writer: SocketWriter, mut ws_reader: SocketReader) {
+        self.client_receiver.close();
+
+        let mut queued: Vec<_> = self.pending.take().into_iter().collect();
+        while let Ok(event) = self.client_receiver.try_recv() {
+            queued.push(event);
+        }
+
+        for event in queued {
+            if let Err(err) = self.send_event(&mut ws_writer, &event).await {
+                self.report(GatewayError::from(err));
+                return;
+            }
+        }
+
+        let frame = CloseFrame {
+            code: CloseCode::Normal,
+            reason: "".into(),
+        };
+        if let Err(err) = ws_writer.send(Message::Close(Some(frame))).await {
+            self.report(GatewayError::from(err));
+            return;
+        }
+
+        // Wait for the server to acknowledge, it will then drop the connection
+        let _ = timeout(CLOSE_TIMEOUT, async {
+            while let Some(Ok(msg)) = ws_reader.next().await {
+                if msg.is_close() {
+                    break;
+                }
+            }
+        })
+        .await;
+    }
+}
+
+/// Unpack nested bulk events, keeping their order.
+pub(crate) fn flatten_bulk(
+    events: Vec<ServerToClientEvent>,
+    out: &mut Vec<Result<ServerToClientEvent, GatewayError>>,
+) {
+    for event in events {
+        match event {
+            ServerToClientEvent::Bulk { v } => flatten_bulk(v, out),
+            event => out.push(Ok(event)),
+        }
+    }
+}
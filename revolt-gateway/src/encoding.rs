@@ -0,0 +1,102 @@
+use crate::{record::FrameData, GatewayError};
+use revolt_models::event::{ClientToServerEvent, KnownEvent, ServerToClientEvent};
+use tokio_tungstenite::tungstenite::Message;
+use url::Url;
+
+/// Wire format spoken with the events server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// JSON text frames
+    #[default]
+    Json,
+    /// MessagePack binary frames
+    MessagePack,
+}
+
+impl Format {
+    /// Value of the `format` query parameter selecting this format.
+    pub fn query_value(self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::MessagePack => "msgpack",
+        }
+    }
+
+    /// Format requested by the `format` query parameter of a connection URL.
+    ///
+    /// Falls back to JSON, which is also what the server does.
+    pub fn from_url(url: &str) -> Self {
+        let url = match Url::parse(url) {
+            Ok(url) => url,
+            Err(_) => return Format::Json,
+        };
+
+        url.query_pairs()
+            .find(|(key, _)| key == "format")
+            .map(|(_, value)| match value.as_ref() {
+                "msgpack" => Format::MessagePack,
+                _ => Format::Json,
+            })
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn encode(self, event: &ClientToServerEvent) -> Result<Message, GatewayError> {
+        Ok(match self {
+            Format::Json => Message::Text(serde_json::to_string(event)?),
+            Format::MessagePack => Message::Binary(rmp_serde::to_vec_named(event)?),
+        })
+    }
+
+    /// Decode a frame, text frames are always JSON.
+    ///
+    /// Known events are decoded straight from the frame, which is only read
+    /// a second time for events of unknown types.
+    ///
+    /// The frame is kept in the error if it can't be decoded.
+    pub(crate) fn decode(self, data: FrameData) -> Result<ServerToClientEvent, GatewayError> {
+        let result: Result<_, Box<dyn std::error::Error + Send + Sync>> = match (&data, self) {
+            (FrameData::Text(text), _) => serde_json::from_str(text)
+                .map(|KnownEvent(event)| event)
+                .or_else(|_| serde_json::from_str(text))
+                .map_err(Into::into),
+            (FrameData::Binary(bytes), Format::Json) => serde_json::from_slice(bytes)
+                .map(|KnownEvent(event)| event)
+                .or_else(|_| serde_json::from_slice(bytes))
+                .map_err(Into::into),
+            (FrameData::Binary(bytes), Format::MessagePack) => rmp_serde::from_slice(bytes)
+                .map(|KnownEvent(event)| event)
+                .or_else(|_| rmp_serde::from_slice(bytes))
+                .map_err(Into::into),
+        };
+
+        result.map_err(|source| GatewayError::DecodeError { source, raw: data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_from_url() {
+        let cases = [
+            ("wss://ws.revolt.chat", Format::Json),
+            ("wss://ws.revolt.chat?format=msgpack", Format::MessagePack),
+            ("wss://ws.revolt.chat?version=1&format=json", Format::Json),
+            (
+                "wss://ws.revolt.chat?format=msgpack#ready",
+                Format::MessagePack,
+            ),
+            (
+                "wss://ws.revolt.chat?%66ormat=msg%70ack",
+                Format::MessagePack,
+            ),
+            ("wss://ws.revolt.chat#?format=msgpack", Format::Json),
+            ("not a url?format=msgpack", Format::Json),
+        ];
+
+        for (url, format) in cases {
+            assert_eq!(Format::from_url(url), format, "{url}");
+        }
+    }
+}
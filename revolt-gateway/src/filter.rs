@@ -0,0 +1,314 @@
+use crate::{encoding::Format, record::FrameData, GatewayError};
+use revolt_models::event::{KnownEvent, ServerToClientEvent};
+use serde::Deserialize;
+use serde_json::{value::RawValue, Value};
+use std::collections::HashSet;
+
+/// Event types the connection itself depends on, these are always decoded.
+const ALWAYS_DECODED: &[&str] = &["Authenticated", "Error", "Pong"];
+
+/// Just the `type` tag of an event, everything else is skipped over.
+#[derive(Deserialize)]
+struct Tag {
+    #[serde(rename = "type")]
+    event_type: String,
+}
+
+#[derive(Deserialize)]
+struct RawBulk<'a> {
+    #[serde(borrow)]
+    v: Vec<&'a RawValue>,
+}
+
+#[derive(Deserialize)]
+struct ValueBulk {
+    v: Vec<Value>,
+}
+
+/// Set of event types to decode and yield from the stream.
+///
+/// Frames of other types are dropped after reading their `type` tag,
+/// without parsing the rest of the payload. This saves a lot of work
+/// on events a bot does not care about, like a large `Ready`.
+///
+/// Types are given by their tag, e.g. `"Message"`. `Authenticated`,
+/// `Error` and `Pong` are needed to run the connection and always
+/// decoded, events inside a `Bulk` are filtered one by one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    /// `None` decodes everything
+    types: Option<HashSet<String>>,
+}
+
+impl EventFilter {
+    /// Decode every event, this is the default.
+    pub fn all() -> Self {
+        EventFilter { types: None }
+    }
+
+    /// Decode only events of the given types.
+    pub fn only<I>(types: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        EventFilter {
+            types: Some(types.into_iter().map(Into::into).collect()),
+        }
+    }
+
+    /// Also decode events of a type.
+    pub fn with(mut self, event_type: impl Into<String>) -> Self {
+        if let Some(types) = &mut self.types {
+            types.insert(event_type.into());
+        }
+        self
+    }
+
+    /// Whether events of a type are decoded.
+    pub fn contains(&self, event_type: &str) -> bool {
+        match &self.types {
+            Some(types) => types.contains(event_type) || ALWAYS_DECODED.contains(&event_type),
+            None => true,
+        }
+    }
+
+    /// Decode a frame the way [crate::RevoltWs] does it.
+    ///
+    /// Returns `None` if the event is filtered out.
+    pub fn decode(
+        &self,
+        format: Format,
+        data: FrameData,
+    ) -> Result<Option<ServerToClientEvent>, GatewayError> {
+        self.decode_frame(format, data, &mut Vec::new())
+    }
+
+    /// Decode a frame, collecting the types of the events that were filtered out.
+    pub(crate) fn decode_frame(
+        &self,
+        format: Format,
+        data: FrameData,
+        skipped: &mut Vec<String>,
+    ) -> Result<Option<ServerToClientEvent>, GatewayError> {
+        if self.types.is_none() {
+            return format.decode(data).map(Some);
+        }
+
+        let tag = match (&data, format) {
+            (FrameData::Text(text), _) => serde_json::from_str::<Tag>(text).ok(),
+            (FrameData::Binary(bytes), Format::Json) => serde_json::from_slice::<Tag>(bytes).ok(),
+            (FrameData::Binary(bytes), Format::MessagePack) => {
+                rmp_serde::from_slice::<Tag>(bytes).ok()
+            }
+        };
+
+        let event_type = match tag {
+            Some(tag) => tag.event_type,
+            // Let the full decode report what is wrong with it
+            None => return format.decode(data).map(Some),
+        };
+
+        if event_type == "Bulk" {
+            return self.decode_bulk(format, data, skipped).map(Some);
+        }
+
+        if self.contains(&event_type) {
+            format.decode(data).map(Some)
+        } else {
+            skipped.push(event_type);
+            Ok(None)
+        }
+    }
+
+    fn decode_bulk(
+        &self,
+        format: Format,
+        data: FrameData,
+        skipped: &mut Vec<String>,
+    ) -> Result<ServerToClientEvent, GatewayError> {
+        let mut v = Vec::new();
+
+        match (&data, format) {
+            (FrameData::Text(text), _) => {
+                let bulk = match serde_json::from_str::<RawBulk>(text) {
+                    Ok(bulk) => bulk,
+                    Err(_) => return format.decode(data),
+                };
+
+                for raw in bulk.v {
+                    let data = FrameData::Text(raw.get().to_string());
+                    v.extend(self.decode_frame(Format::Json, data, skipped)?);
+                }
+            }
+            (FrameData::Binary(bytes), _) => {
+                // MessagePack has no borrowed raw values, go through JSON values instead
+                let bulk = match format {
+                    Format::Json => serde_json::from_slice::<ValueBulk>(bytes).ok(),
+                    Format::MessagePack => rmp_serde::from_slice::<ValueBulk>(bytes).ok(),
+                };
+                let bulk = match bulk {
+                    Some(bulk) => bulk,
+                    None => return format.decode(data),
+                };
+
+                for value in bulk.v {
+                    match value.get("type").and_then(Value::as_str) {
+                        Some("Bulk") => {
+                            let data = FrameData::Text(value.to_string());
+                            v.extend(self.decode_frame(Format::Json, data, skipped)?);
+                            continue;
+                        }
+                        Some(event_type) if !self.contains(event_type) => {
+                            skipped.push(event_type.to_string());
+                            continue;
+                        }
+                        _ => {}
+                    }
+
+                    let event = KnownEvent::deserialize(&value)
+                        .map(|KnownEvent(event)| event)
+                        .or_else(|_| ServerToClientEvent::deserialize(&value));
+                    match event {
+                        Ok(event) => v.push(event),
+                        Err(err) => {
+                            return Err(GatewayError::DecodeError {
+                                source: err.into(),
+                                raw: FrameData::Text(value.to_string()),
+                            })
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(ServerToClientEvent::Bulk { v })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const FORMATS: [Format; 2] = [Format::Json, Format::MessagePack];
+
+    fn frame(format: Format, value: &Value) -> FrameData {
+        match format {
+            Format::Json => FrameData::Text(value.to_string()),
+            Format::MessagePack => FrameData::Binary(rmp_serde::to_vec_named(value).unwrap()),
+        }
+    }
+
+    fn message() -> Value {
+        json!({ "type": "Message", "_id": "M", "channel": "C", "author": "U" })
+    }
+
+    fn typing() -> Value {
+        json!({ "type": "ChannelStartTyping", "id": "C", "user": "U" })
+    }
+
+    fn bulk(v: Vec<Value>) -> Value {
+        json!({ "type": "Bulk", "v": v })
+    }
+
+    /// Event types, with the contents of bulk events in brackets.
+    fn describe(event: &ServerToClientEvent) -> String {
+        match event {
+            ServerToClientEvent::Bulk { v } => {
+                let v: Vec<String> = v.iter().map(describe).collect();
+                format!("Bulk[{}]", v.join(", "))
+            }
+            ServerToClientEvent::Message { .. } => "Message".to_string(),
+            ServerToClientEvent::Pong { .. } => "Pong".to_string(),
+            ServerToClientEvent::Authenticated => "Authenticated".to_string(),
+            ServerToClientEvent::Error { .. } => "Error".to_string(),
+            ServerToClientEvent::ChannelStartTyping { .. } => "ChannelStartTyping".to_string(),
+            event => panic!("unexpected event {event:?}"),
+        }
+    }
+
+    fn decode(
+        filter: &EventFilter,
+        format: Format,
+        value: &Value,
+    ) -> (Option<String>, Vec<String>) {
+        let mut skipped = Vec::new();
+        let event = filter
+            .decode_frame(format, frame(format, value), &mut skipped)
+            .unwrap();
+        (event.as_ref().map(describe), skipped)
+    }
+
+    #[test]
+    fn bulk_contents_are_filtered() {
+        let filter = EventFilter::only(["Message"]);
+        let value = bulk(vec![
+            typing(),
+            message(),
+            json!({ "type": "Pong", "data": 1 }),
+        ]);
+
+        for format in FORMATS {
+            let (event, skipped) = decode(&filter, format, &value);
+            assert_eq!(event.as_deref(), Some("Bulk[Message, Pong]"), "{format:?}");
+            assert_eq!(skipped, ["ChannelStartTyping"], "{format:?}");
+        }
+    }
+
+    #[test]
+    fn nested_bulk_is_filtered() {
+        let filter = EventFilter::only(["Message"]);
+        let value = bulk(vec![bulk(vec![message(), typing()]), typing(), message()]);
+
+        for format in FORMATS {
+            let (event, skipped) = decode(&filter, format, &value);
+            assert_eq!(
+                event.as_deref(),
+                Some("Bulk[Bulk[Message], Message]"),
+                "{format:?}"
+            );
+            assert_eq!(skipped, ["ChannelStartTyping"; 2], "{format:?}");
+        }
+    }
+
+    #[test]
+    fn connection_events_are_always_decoded() {
+        let filter = EventFilter::only(Vec::<String>::new());
+        let events = [
+            json!({ "type": "Authenticated" }),
+            json!({ "type": "Error", "error": { "type": "InternalError" } }),
+            json!({ "type": "Pong", "data": 1 }),
+        ];
+
+        for format in FORMATS {
+            for value in &events {
+                let (event, skipped) = decode(&filter, format, value);
+                assert_eq!(event.as_deref(), value["type"].as_str(), "{format:?}");
+                assert!(skipped.is_empty());
+            }
+
+            let (event, skipped) = decode(&filter, format, &message());
+            assert_eq!(event, None);
+            assert_eq!(skipped, ["Message"]);
+        }
+    }
+
+    #[test]
+    fn bad_tag_reports_full_decode_error() {
+        let filter = EventFilter::only(["Message"]);
+
+        for format in FORMATS {
+            for value in [json!({ "type": 5 }), json!({ "data": 1 }), json!([1, 2])] {
+                let result = filter.decode(format, frame(format, &value));
+                assert!(
+                    matches!(result, Err(GatewayError::DecodeError { .. })),
+                    "{format:?} {value}"
+                );
+            }
+        }
+
+        let result = filter.decode(Format::Json, FrameData::Text("{\"type\":".to_string()));
+        assert!(matches!(result, Err(GatewayError::DecodeError { .. })));
+    }
+}
@@ -0,0 +1,185 @@
+use revolt_models::event::ClientToServerEvent;
+use std::{collections::VecDeque, time::Duration};
+use tokio::{sync::watch, time::Instant};
+
+/// Number of round trips the average latency is computed over.
+const SAMPLES: usize = 10;
+
+/// Heartbeat round trip times.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Latency {
+    /// Round trip time of the most recent heartbeat
+    pub latest: Option<Duration>,
+    /// Average round trip time of the recent heartbeats
+    pub average: Option<Duration>,
+}
+
+/// Tracks pings in flight and matches them against returned pongs.
+#[derive(Debug)]
+pub(crate) struct Heartbeat {
+    counter: i32,
+    in_flight: VecDeque<(i32, Instant)>,
+    samples: VecDeque<Duration>,
+    latency: watch::Sender<Latency>,
+}
+
+impl Heartbeat {
+    pub(crate) fn new(latency: watch::Sender<Latency>) -> Self {
+        Heartbeat {
+            counter: 0,
+            in_flight: VecDeque::new(),
+            samples: VecDeque::with_capacity(SAMPLES),
+            latency,
+        }
+    }
+
+    /// Forget pings sent over a previous connection.
+    pub(crate) fn reset(&mut self) {
+        self.in_flight.clear();
+    }
+
+    /// Create the next ping, carrying a unique counter value.
+    pub(crate) fn ping(&mut self) -> ClientToServerEvent {
+        // Server echoes the value back as unsigned, so stay within the non-negative range
+        self.counter = self.counter.wrapping_add(1) & i32::MAX;
+        self.in_flight.push_back((self.counter, Instant::now()));
+
+        ClientToServerEvent::Ping { data: self.counter }
+    }
+
+    /// Record the pong for a previously sent ping.
+    pub(crate) fn pong(&mut self, data: u32) {
+        let position = match self
+            .in_flight
+            .iter()
+            .position(|(counter, _)| *counter as u32 == data)
+        {
+            Some(position) => position,
+            None => return,
+        };
+
+        let (_, sent_at) = self.in_flight[position];
+        let rtt = sent_at.elapsed();
+
+        // Pongs arrive in order, anything sent earlier is not going to be answered
+        self.in_flight.drain(..=position);
+
+        if self.samples.len() == SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(rtt);
+
+        let average = self.samples.iter().sum::<Duration>() / self.samples.len() as u32;
+        self.latency.send_replace(Latency {
+            latest: Some(rtt),
+            average: Some(average),
+        });
+    }
+
+    /// Instant by which the oldest unanswered ping has to be answered.
+    pub(crate) fn deadline(&self, timeout: Duration) -> Option<Instant> {
+        self.in_flight
+            .front()
+            .map(|(_, sent_at)| *sent_at + timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::advance;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    fn heartbeat() -> (Heartbeat, watch::Receiver<Latency>) {
+        let (sender, receiver) = watch::channel(Latency::default());
+        (Heartbeat::new(sender), receiver)
+    }
+
+    fn data(ping: ClientToServerEvent) -> u32 {
+        match ping {
+            ClientToServerEvent::Ping { data } => data as u32,
+            event => panic!("not a ping: {event:?}"),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn pong_drains_earlier_pings() {
+        let (mut heartbeat, latency) = heartbeat();
+
+        let first = data(heartbeat.ping());
+        advance(10 * MS).await;
+        let second = data(heartbeat.ping());
+        advance(10 * MS).await;
+        let third = data(heartbeat.ping());
+        advance(5 * MS).await;
+
+        // Out of order, the first ping is given up on
+        heartbeat.pong(second);
+        assert_eq!(latency.borrow().latest, Some(15 * MS));
+        assert_eq!(heartbeat.in_flight.len(), 1);
+
+        // Stale and unknown pongs are ignored
+        heartbeat.pong(first);
+        heartbeat.pong(1000);
+        assert_eq!(latency.borrow().latest, Some(15 * MS));
+        assert_eq!(heartbeat.in_flight.len(), 1);
+
+        heartbeat.pong(third);
+        assert_eq!(latency.borrow().latest, Some(5 * MS));
+        assert!(heartbeat.in_flight.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn counter_wraps_to_zero() {
+        let (mut heartbeat, latency) = heartbeat();
+        heartbeat.counter = i32::MAX - 1;
+
+        let last = data(heartbeat.ping());
+        let wrapped = data(heartbeat.ping());
+        assert_eq!(last, i32::MAX as u32);
+        assert_eq!(wrapped, 0);
+
+        advance(MS).await;
+        heartbeat.pong(last);
+        assert_eq!(heartbeat.in_flight.len(), 1);
+        heartbeat.pong(wrapped);
+        assert!(heartbeat.in_flight.is_empty());
+        assert_eq!(latency.borrow().latest, Some(MS));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn average_covers_recent_samples() {
+        let (mut heartbeat, latency) = heartbeat();
+
+        for i in 1..=SAMPLES as u32 + 2 {
+            let ping = data(heartbeat.ping());
+            advance(i * MS).await;
+            heartbeat.pong(ping);
+        }
+
+        // Round trips of 3 to 12 milliseconds
+        let latency = *latency.borrow();
+        assert_eq!(latency.latest, Some(12 * MS));
+        assert_eq!(latency.average, Some(Duration::from_micros(7500)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn deadline_follows_oldest_unanswered_ping() {
+        let (mut heartbeat, _) = heartbeat();
+        let timeout = Duration::from_secs(10);
+        assert_eq!(heartbeat.deadline(timeout), None);
+
+        let start = Instant::now();
+        let first = data(heartbeat.ping());
+        advance(MS).await;
+        heartbeat.ping();
+        assert_eq!(heartbeat.deadline(timeout), Some(start + timeout));
+
+        heartbeat.pong(first);
+        assert_eq!(heartbeat.deadline(timeout), Some(start + MS + timeout));
+
+        heartbeat.reset();
+        assert_eq!(heartbeat.deadline(timeout), None);
+    }
+}
@@ -1,90 +1,290 @@
-use futures_util::{SinkExt, Stream, StreamExt};
+#[cfg(feature = "backfill")]
+mod backfill;
+mod backoff;
+mod builder;
+mod connection;
+mod encoding;
+mod filter;
+mod heartbeat;
+mod queue;
+mod record;
+mod replay;
+mod state;
+mod supervisor;
+mod typing;
+
+#[cfg(feature = "backfill")]
+pub use backfill::Backfill;
+pub use builder::RevoltWsBuilder;
+pub use connection::ConnectionEvent;
+pub use encoding::Format;
+pub use filter::EventFilter;
+pub use heartbeat::Latency;
+pub use queue::{OverflowPolicy, QueueMetrics};
+pub use record::{Direction, Frame, FrameData};
+pub use replay::{Pacing, ReplayWs};
+pub use state::{CloseReason, ConnectionState};
+pub use supervisor::{Supervisor, SupervisorHandle};
+pub use tokio_tungstenite::{tungstenite::protocol::WebSocketConfig, Connector};
+pub use typing::TypingGuard;
+
+use connection::{Config, Driver};
+use futures_util::Stream;
+use heartbeat::Heartbeat;
+use queue::EventReceiver;
 use revolt_models::event::{ClientToServerEvent, ServerToClientEvent};
-use std::time::Duration;
+use std::future::Future;
 use tokio::{
     spawn,
-    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
-    time::sleep,
+    sync::{
+        broadcast,
+        mpsc::{self, Sender},
+        oneshot, watch,
+    },
+    task::JoinHandle,
 };
-use tokio_tungstenite::tungstenite::Message;
 
 #[derive(Debug, thiserror::Error)]
 pub enum GatewayError {
     #[error("Tungstenite error: {0}")]
-    WsError(#[from] tokio_tungstenite::tungstenite::Error),
+    WsError(Box<tokio_tungstenite::tungstenite::Error>),
 
     #[error("Serde JSON deserialization/serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
+
+    #[error("MessagePack serialization error: {0}")]
+    MessagePackEncodeError(#[from] rmp_serde::encode::Error),
+
+    #[error("MessagePack deserialization error: {0}")]
+    MessagePackDecodeError(#[from] rmp_serde::decode::Error),
+
+    #[error("Failed to decode event: {source}")]
+    DecodeError {
+        source: Box<dyn std::error::Error + Send + Sync>,
+        /// Frame as received
+        raw: FrameData,
+    },
+
+    #[cfg(feature = "backfill")]
+    #[error("Failed to fetch missed messages: {0}")]
+    BackfillError(#[from] revolt_http::RevoltHttpError),
+
+    #[cfg(feature = "backfill")]
+    #[error("Fetching missed messages timed out, {channels} channels were skipped")]
+    BackfillTimeout { channels: usize },
+
+    #[error("Invalid URL: {0}")]
+    UrlError(#[from] url::ParseError),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Event queue is full, {dropped} events were dropped")]
+    QueueOverflow { dropped: u64 },
+
+    #[error("Connection is closed")]
+    Closed,
+
+    #[error("Gateway task failed: {0}")]
+    TaskError(#[from] tokio::task::JoinError),
 }
 
+impl From<tokio_tungstenite::tungstenite::Error> for GatewayError {
+    fn from(err: tokio_tungstenite::tungstenite::Error) -> Self {
+        // Boxed as it is by far the largest variant
+        GatewayError::WsError(Box::new(err))
+    }
+}
+
+/// Connection to the Revolt events server.
+///
+/// The connection authenticates by itself and transparently reconnects
+/// with exponential backoff whenever the socket drops, so the stream keeps
+/// yielding events across reconnects.
+///
+/// Dropping it closes the connection in the background, use
+/// [RevoltWs::close] to wait for that to finish.
 #[derive(Debug)]
 pub struct RevoltWs {
-    server_event_receiver: UnboundedReceiver<Result<ServerToClientEvent, GatewayError>>,
-    client_event_sender: UnboundedSender<ClientToServerEvent>,
+    server_event_receiver: EventReceiver,
+    client_event_sender: Sender<ClientToServerEvent>,
+    connection_events: broadcast::Sender<ConnectionEvent>,
+    latency: watch::Receiver<Latency>,
+    state: watch::Receiver<ConnectionState>,
+    close_sender: Option<oneshot::Sender<()>>,
+    driver: Option<JoinHandle<Config>>,
 }
 
 impl RevoltWs {
-    pub async fn connect() -> Result<RevoltWs, GatewayError> {
-        RevoltWs::connect_with_url("wss://ws.revolt.chat".to_string()).await
+    pub async fn connect(token: impl Into<String>) -> Result<RevoltWs, GatewayError> {
+        RevoltWs::builder(token).connect().await
+    }
+
+    /// Connect to a custom events server.
+    ///
+    /// The wire format is picked from the `format` query parameter of the URL,
+    /// e.g. `wss://ws.revolt.chat?format=msgpack`, and defaults to JSON.
+    pub async fn connect_with_url(
+        url: impl Into<String>,
+        token: impl Into<String>,
+    ) -> Result<Self, GatewayError> {
+        RevoltWs::builder(token).url(url).connect().await
+    }
+
+    /// Configure the connection before connecting.
+    pub fn builder(token: impl Into<String>) -> RevoltWsBuilder {
+        RevoltWsBuilder::new(token)
     }
 
-    pub async fn connect_with_url(url: String) -> Result<Self, GatewayError> {
-        let (ws, _) = tokio_tungstenite::connect_async(url).await?;
-        let (mut ws_writer, mut ws_reader) = ws.split();
+    pub(crate) async fn connect_with_config(config: Config) -> Result<Self, GatewayError> {
+        // The first connection error is returned to the caller, later ones are retried
+        let ws = connection::connect_socket(&config).await?;
 
         let (server_sender, server_receiver) =
-            mpsc::unbounded_channel::<Result<ServerToClientEvent, GatewayError>>();
-        let (client_sender, mut client_receiver) = mpsc::unbounded_channel::<ClientToServerEvent>();
+            queue::channel(config.incoming_capacity, config.overflow_policy);
+        let (client_sender, client_receiver) = mpsc::channel(config.outgoing_capacity);
+        let (connection_events, _) = broadcast::channel(16);
+        let (latency_sender, latency) = watch::channel(Latency::default());
+        let (state_sender, state) = watch::channel(ConnectionState::Connecting);
+        let (close_sender, close_receiver) = oneshot::channel();
 
-        let c = RevoltWs {
-            server_event_receiver: server_receiver,
-            client_event_sender: client_sender.clone(),
+        let driver = Driver {
+            config,
+            server_sender,
+            client_receiver,
+            connection_events: connection_events.clone(),
+            heartbeat: Heartbeat::new(latency_sender),
+            state: state_sender,
+            close_receiver,
+            pending: None,
+            reconnected: None,
         };
+        let driver = spawn(driver.run(ws));
 
-        // Receive message -> decode -> send to channel
-        spawn(async move {
-            while let Some(msg) = ws_reader.next().await {
-                // FIXME: what the fuck is this unreadable shit
-                let event = match msg {
-                    Ok(msg) => match msg.to_text() {
-                        Ok(text) => match serde_json::from_str::<ServerToClientEvent>(text) {
-                            Ok(res) => Ok(res),
-                            Err(err) => Err(GatewayError::from(err)),
-                        },
-                        Err(err) => Err(GatewayError::from(err)),
-                    },
-                    Err(err) => Err(GatewayError::from(err)),
-                };
-
-                server_sender.send(event).unwrap();
-            }
-        });
-
-        // Receive from channel -> encode -> send message
-        spawn(async move {
-            while let Some(event) = client_receiver.recv().await {
-                let msg = Message::Text(serde_json::to_string(&event).unwrap());
-                ws_writer.send(msg).await.unwrap();
-            }
-        });
-
-        // Send heartbeat periodically
-        spawn(async move {
-            loop {
-                client_sender
-                    .send(ClientToServerEvent::Ping { data: 0 })
-                    .unwrap();
-                sleep(Duration::from_secs(15)).await;
-            }
-        });
-
-        Ok(c)
+        Ok(RevoltWs {
+            server_event_receiver: server_receiver,
+            client_event_sender: client_sender,
+            connection_events,
+            latency,
+            state,
+            close_sender: Some(close_sender),
+            driver: Some(driver),
+        })
     }
 
     pub async fn send(&mut self, event: ClientToServerEvent) -> Result<(), GatewayError> {
-        self.client_event_sender.send(event).unwrap();
+        self.client_event_sender
+            .send(event)
+            .await
+            .map_err(|_| GatewayError::Closed)
+    }
+
+    /// Let others know we started typing in a channel.
+    pub async fn begin_typing(
+        &mut self,
+        channel_id: impl Into<String>,
+    ) -> Result<(), GatewayError> {
+        self.send(ClientToServerEvent::BeginTyping {
+            channel: channel_id.into(),
+        })
+        .await
+    }
+
+    /// Let others know we stopped typing in a channel.
+    pub async fn end_typing(&mut self, channel_id: impl Into<String>) -> Result<(), GatewayError> {
+        self.send(ClientToServerEvent::EndTyping {
+            channel: channel_id.into(),
+        })
+        .await
+    }
+
+    /// Show the typing indicator in a channel for as long as the guard lives.
+    ///
+    /// The indicator is renewed periodically and removed once the guard is dropped.
+    pub fn typing(&self, channel_id: impl Into<String>) -> TypingGuard {
+        TypingGuard::start(self.client_event_sender.clone(), channel_id.into())
+    }
+
+    /// Show the typing indicator in a channel while a future runs.
+    ///
+    /// The indicator is removed when the future completes, whatever its result,
+    /// or when it is cancelled.
+    pub async fn while_typing<F: Future>(
+        &self,
+        channel_id: impl Into<String>,
+        future: F,
+    ) -> F::Output {
+        let _guard = self.typing(channel_id);
+        future.await
+    }
+
+    /// Receive member presence updates of a server.
+    ///
+    /// The subscription lasts for 15 minutes and has to be renewed to continue.
+    pub async fn subscribe_server(
+        &mut self,
+        server_id: impl Into<String>,
+    ) -> Result<(), GatewayError> {
+        self.send(ClientToServerEvent::Subscribe {
+            server_id: server_id.into(),
+        })
+        .await
+    }
+
+    /// Gracefully close the connection.
+    ///
+    /// Events queued with [RevoltWs::send] are sent before the close frame.
+    /// Events received before closing can still be read from the stream,
+    /// after which it ends.
+    pub async fn close(&mut self) -> Result<(), GatewayError> {
+        self.shutdown().await.map(drop)
+    }
+
+    /// Close the connection and take back its config.
+    pub(crate) async fn shutdown(&mut self) -> Result<Config, GatewayError> {
+        if let Some(close_sender) = self.close_sender.take() {
+            // Driver is already gone if this fails, which is what we want anyway
+            let _ = close_sender.send(());
+        }
+
+        match self.driver.take() {
+            Some(driver) => Ok(driver.await?),
+            None => Err(GatewayError::Closed),
+        }
+    }
+
+    /// Subscribe to disconnect and reconnect notifications.
+    pub fn connection_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.connection_events.subscribe()
+    }
+
+    /// Current state of the connection.
+    pub fn state(&self) -> ConnectionState {
+        self.state.borrow().clone()
+    }
+
+    /// Watch the connection state, e.g. to wait until it is [ConnectionState::Ready].
+    pub fn state_changes(&self) -> watch::Receiver<ConnectionState> {
+        self.state.clone()
+    }
+
+    /// Current depth of the event queues.
+    pub fn queue_metrics(&self) -> QueueMetrics {
+        let mut metrics = QueueMetrics {
+            outgoing: self.client_event_sender.max_capacity() - self.client_event_sender.capacity(),
+            outgoing_capacity: self.client_event_sender.max_capacity(),
+            ..Default::default()
+        };
+        self.server_event_receiver.metrics(&mut metrics);
+
+        metrics
+    }
 
-        Ok(())
+    /// Latest and average heartbeat round trip time.
+    ///
+    /// Both are `None` until the first pong arrives.
+    pub fn latency(&self) -> Latency {
+        *self.latency.borrow()
     }
 }
 
@@ -0,0 +1,374 @@
+use crate::GatewayError;
+use revolt_models::event::ServerToClientEvent;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll, Waker},
+};
+use tokio::sync::Notify;
+
+type Item = Result<ServerToClientEvent, GatewayError>;
+
+/// What to do with a received event when the incoming queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Stop reading from the socket until there is space again
+    ///
+    /// A consumer stalling for longer than the heartbeat timeout
+    /// makes the connection look dead and causes a reconnect.
+    #[default]
+    Block,
+    /// Drop the oldest queued event
+    DropOldest,
+    /// Drop events that are safe to miss, like typing indicators,
+    /// and block if only important events are queued
+    DropNonCritical,
+    /// Drop the new event and yield [GatewayError::QueueOverflow] from the stream
+    Error,
+}
+
+/// Snapshot of the event queues.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueueMetrics {
+    /// Events received but not read from the stream yet
+    pub incoming: usize,
+    /// Maximum number of events held in the incoming queue
+    pub incoming_capacity: usize,
+    /// Highest number of events the incoming queue has held
+    pub incoming_peak: usize,
+    /// Received events dropped by the overflow policy
+    pub dropped: u64,
+    /// Events waiting to be sent
+    pub outgoing: usize,
+    /// Maximum number of events held in the outgoing queue
+    pub outgoing_capacity: usize,
+}
+
+/// Whether missing the item could leave cached state out of date.
+///
+/// Only typing indicators and pongs can be dropped, acks move the read
+/// position of a channel.
+fn is_critical(item: &Item) -> bool {
+    !matches!(
+        item,
+        Ok(ServerToClientEvent::ChannelStartTyping { .. })
+            | Ok(ServerToClientEvent::ChannelStopTyping { .. })
+            | Ok(ServerToClientEvent::Pong { .. })
+    )
+}
+
+#[derive(Debug)]
+struct State {
+    items: VecDeque<Item>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    receiver_waker: Option<Waker>,
+    sender_closed: bool,
+    receiver_closed: bool,
+    peak: usize,
+    dropped: u64,
+    /// Events dropped by [OverflowPolicy::Error] not reported yet
+    unreported: u64,
+}
+
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<State>,
+    space: Notify,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // Nothing in here can panic while holding the lock, so it is never poisoned
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// Incoming event queue bounded according to an [OverflowPolicy].
+pub(crate) fn channel(capacity: usize, policy: OverflowPolicy) -> (EventSender, EventReceiver) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            items: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            policy,
+            receiver_waker: None,
+            sender_closed: false,
+            receiver_closed: false,
+            peak: 0,
+            dropped: 0,
+            unreported: 0,
+        }),
+        space: Notify::new(),
+    });
+
+    (
+        EventSender {
+            shared: Arc::clone(&shared),
+        },
+        EventReceiver { shared },
+    )
+}
+
+/// Receiving half of the queue is gone.
+#[derive(Debug)]
+pub(crate) struct QueueClosed;
+
+#[derive(Debug)]
+pub(crate) struct EventSender {
+    shared: Arc<Shared>,
+}
+
+impl EventSender {
+    /// Queue an event, applying the overflow policy if the queue is full.
+    ///
+    /// Fails if the receiver is gone.
+    pub(crate) async fn send(&self, item: Item) -> Result<(), QueueClosed> {
+        loop {
+            {
+                let mut state = self.shared.lock();
+                if state.receiver_closed {
+                    return Err(QueueClosed);
+                }
+
+                if state.items.len() < state.capacity {
+                    state.push(item);
+                    return Ok(());
+                }
+
+                match state.policy {
+                    OverflowPolicy::Block => {}
+                    OverflowPolicy::DropOldest => {
+                        state.items.pop_front();
+                        state.dropped += 1;
+                        state.push(item);
+                        return Ok(());
+                    }
+                    OverflowPolicy::DropNonCritical => {
+                        if !is_critical(&item) {
+                            state.dropped += 1;
+                            return Ok(());
+                        }
+
+                        if let Some(position) = state.items.iter().position(|i| !is_critical(i)) {
+                            state.items.remove(position);
+                            state.dropped += 1;
+                            state.push(item);
+                            return Ok(());
+                        }
+                    }
+                    OverflowPolicy::Error => {
+                        state.dropped += 1;
+                        state.unreported += 1;
+                        state.wake();
+                        return Ok(());
+                    }
+                }
+            }
+
+            self.shared.space.notified().await;
+        }
+    }
+
+    /// Queue an event regardless of the capacity, used for rare errors.
+    pub(crate) fn force_send(&self, item: Item) -> Result<(), QueueClosed> {
+        let mut state = self.shared.lock();
+        if state.receiver_closed {
+            return Err(QueueClosed);
+        }
+
+        state.push(item);
+        Ok(())
+    }
+}
+
+impl Drop for EventSender {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.sender_closed = true;
+        state.wake();
+    }
+}
+
+impl State {
+    fn push(&mut self, item: Item) {
+        self.items.push_back(item);
+        self.peak = self.peak.max(self.items.len());
+        self.wake();
+    }
+
+    fn wake(&mut self) {
+        if let Some(waker) = self.receiver_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct EventReceiver {
+    shared: Arc<Shared>,
+}
+
+impl EventReceiver {
+    pub(crate) fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Item>> {
+        let mut state = self.shared.lock();
+
+        if state.unreported > 0 {
+            let dropped = std::mem::take(&mut state.unreported);
+            return Poll::Ready(Some(Err(GatewayError::QueueOverflow { dropped })));
+        }
+
+        if let Some(item) = state.items.pop_front() {
+            self.shared.space.notify_one();
+            return Poll::Ready(Some(item));
+        }
+
+        if state.sender_closed {
+            return Poll::Ready(None);
+        }
+
+        state.receiver_waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    /// Fill in the incoming side of the metrics.
+    pub(crate) fn metrics(&self, metrics: &mut QueueMetrics) {
+        let state = self.shared.lock();
+        metrics.incoming = state.items.len();
+        metrics.incoming_capacity = state.capacity;
+        metrics.incoming_peak = state.peak;
+        metrics.dropped = state.dropped;
+    }
+}
+
+impl Drop for EventReceiver {
+    fn drop(&mut self) {
+        self.shared.lock().receiver_closed = true;
+        // Let a blocked sender notice
+        self.shared.space.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::future::poll_fn;
+    use tokio::{spawn, task::yield_now};
+
+    fn critical(id: &str) -> Item {
+        Ok(ServerToClientEvent::ChannelDelete { id: id.to_string() })
+    }
+
+    fn typing(id: &str) -> Item {
+        Ok(ServerToClientEvent::ChannelStartTyping {
+            id: id.to_string(),
+            user: "user".to_string(),
+        })
+    }
+
+    /// Short description of a received item to compare against.
+    async fn recv(receiver: &mut EventReceiver) -> Option<String> {
+        let item = poll_fn(|cx| receiver.poll_recv(cx)).await?;
+        Some(match item {
+            Ok(ServerToClientEvent::ChannelDelete { id }) => id,
+            Ok(ServerToClientEvent::ChannelStartTyping { id, .. }) => format!("typing {id}"),
+            Err(GatewayError::QueueOverflow { dropped }) => format!("dropped {dropped}"),
+            item => panic!("unexpected item {item:?}"),
+        })
+    }
+
+    fn dropped(receiver: &EventReceiver) -> u64 {
+        let mut metrics = QueueMetrics::default();
+        receiver.metrics(&mut metrics);
+        metrics.dropped
+    }
+
+    #[tokio::test]
+    async fn block_wakes_sender_once_there_is_space() {
+        let (sender, mut receiver) = channel(1, OverflowPolicy::Block);
+        sender.send(critical("a")).await.unwrap();
+
+        let blocked = spawn(async move { sender.send(critical("b")).await });
+        yield_now().await;
+        assert!(!blocked.is_finished());
+
+        assert_eq!(recv(&mut receiver).await.unwrap(), "a");
+        blocked.await.unwrap().unwrap();
+        assert_eq!(recv(&mut receiver).await.unwrap(), "b");
+        assert_eq!(dropped(&receiver), 0);
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_makes_room_for_new_events() {
+        let (sender, mut receiver) = channel(2, OverflowPolicy::DropOldest);
+        for id in ["a", "b", "c"] {
+            sender.send(critical(id)).await.unwrap();
+        }
+        drop(sender);
+
+        assert_eq!(recv(&mut receiver).await.unwrap(), "b");
+        assert_eq!(recv(&mut receiver).await.unwrap(), "c");
+        assert_eq!(recv(&mut receiver).await, None);
+        assert_eq!(dropped(&receiver), 1);
+    }
+
+    #[tokio::test]
+    async fn drop_non_critical_blocks_when_only_critical_events_are_queued() {
+        let (sender, mut receiver) = channel(2, OverflowPolicy::DropNonCritical);
+        sender.send(typing("1")).await.unwrap();
+        sender.send(critical("a")).await.unwrap();
+
+        // New typing event is dropped, a critical one replaces the queued typing event
+        sender.send(typing("2")).await.unwrap();
+        sender.send(critical("b")).await.unwrap();
+        assert_eq!(dropped(&receiver), 2);
+
+        let blocked = spawn(async move { sender.send(critical("c")).await });
+        yield_now().await;
+        assert!(!blocked.is_finished());
+
+        assert_eq!(recv(&mut receiver).await.unwrap(), "a");
+        blocked.await.unwrap().unwrap();
+        assert_eq!(recv(&mut receiver).await.unwrap(), "b");
+        assert_eq!(recv(&mut receiver).await.unwrap(), "c");
+        assert_eq!(dropped(&receiver), 2);
+    }
+
+    #[tokio::test]
+    async fn error_reports_dropped_events_first() {
+        let (sender, mut receiver) = channel(1, OverflowPolicy::Error);
+        for id in ["a", "b", "c"] {
+            sender.send(critical(id)).await.unwrap();
+        }
+
+        assert_eq!(recv(&mut receiver).await.unwrap(), "dropped 2");
+        assert_eq!(recv(&mut receiver).await.unwrap(), "a");
+
+        // Reported once only
+        sender.send(critical("d")).await.unwrap();
+        assert_eq!(recv(&mut receiver).await.unwrap(), "d");
+        assert_eq!(dropped(&receiver), 2);
+    }
+
+    #[tokio::test]
+    async fn blocked_sender_fails_when_receiver_is_dropped() {
+        let (sender, receiver) = channel(1, OverflowPolicy::Block);
+        sender.send(critical("a")).await.unwrap();
+
+        let blocked = spawn(async move { sender.send(critical("b")).await });
+        yield_now().await;
+        assert!(!blocked.is_finished());
+
+        drop(receiver);
+        assert!(matches!(blocked.await.unwrap(), Err(QueueClosed)));
+    }
+
+    #[test]
+    fn acks_are_critical() {
+        assert!(is_critical(&Ok(ServerToClientEvent::ChannelAck {
+            id: "channel".to_string(),
+            user: "user".to_string(),
+            message_id: "message".to_string(),
+        })));
+        assert!(!is_critical(&typing("a")));
+    }
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    fs::OpenOptions,
+    io::{AsyncWriteExt, BufWriter},
+    spawn,
+    sync::mpsc::{self, UnboundedSender},
+};
+use tokio_tungstenite::tungstenite::Message;
+
+/// Which way a recorded frame went.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// Sent by the server
+    Incoming,
+    /// Sent by us
+    Outgoing,
+}
+
+/// Content of a recorded frame.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", content = "data", rename_all = "lowercase")]
+pub enum FrameData {
+    /// JSON text frame
+    Text(String),
+    /// MessagePack binary frame
+    Binary(Vec<u8>),
+}
+
+/// Single line of a session recording.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+    /// Which way the frame went
+    pub direction: Direction,
+    /// Frame content
+    #[serde(flatten)]
+    pub data: FrameData,
+}
+
+/// Appends frames to a JSONL file in the background.
+#[derive(Debug, Clone)]
+pub(crate) struct Recorder {
+    sender: UnboundedSender<Frame>,
+}
+
+impl Recorder {
+    pub(crate) async fn create(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        let (sender, mut receiver) = mpsc::unbounded_channel::<Frame>();
+
+        spawn(async move {
+            let mut writer = BufWriter::new(file);
+
+            while let Some(frame) = receiver.recv().await {
+                // Write whatever piled up, then flush so the file is usable while running
+                let mut next = Some(frame);
+                while let Some(frame) = next {
+                    if let Ok(mut line) = serde_json::to_vec(&frame) {
+                        line.push(b'\n');
+                        if writer.write_all(&line).await.is_err() {
+                            return;
+                        }
+                    }
+                    next = receiver.try_recv().ok();
+                }
+
+                if writer.flush().await.is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(Recorder { sender })
+    }
+
+    /// Record a data frame, control frames are skipped.
+    pub(crate) fn record(&self, direction: Direction, msg: &Message) {
+        let data = match msg {
+            Message::Text(text) => FrameData::Text(text.clone()),
+            Message::Binary(bytes) => FrameData::Binary(bytes.clone()),
+            _ => return,
+        };
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_millis() as u64)
+            .unwrap_or_default();
+
+        // Recording stops silently if the file can't be written anymore
+        let _ = self.sender.send(Frame {
+            timestamp,
+            direction,
+            data,
+        });
+    }
+}
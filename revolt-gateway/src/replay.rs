@@ -0,0 +1,202 @@
+use crate::{
+    connection::flatten_bulk,
+    encoding::Format,
+    record::{Direction, Frame},
+    GatewayError,
+};
+use futures_util::Stream;
+use revolt_models::event::ServerToClientEvent;
+use std::{path::Path, time::Duration};
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, BufReader, Lines},
+    spawn,
+    sync::mpsc::{self, Receiver, Sender},
+    time::sleep,
+};
+
+type Item = Result<ServerToClientEvent, GatewayError>;
+
+/// How fast recorded frames are replayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pacing {
+    /// Keep the delays between frames as they were recorded
+    RealTime,
+    /// Yield frames as soon as they are read
+    AsFastAsPossible,
+}
+
+/// Replays the events of a session recording.
+///
+/// Recordings are made with [crate::RevoltWsBuilder::record]. Only incoming
+/// frames are replayed, decoded the same way [crate::RevoltWs] does it,
+/// with bulk events flattened, so handlers can be tested against real traffic offline.
+#[derive(Debug)]
+pub struct ReplayWs {
+    receiver: Receiver<Item>,
+}
+
+impl ReplayWs {
+    pub async fn open(path: impl AsRef<Path>, pacing: Pacing) -> Result<Self, GatewayError> {
+        let file = File::open(path).await?;
+        let (sender, receiver) = mpsc::channel(64);
+
+        spawn(replay(BufReader::new(file).lines(), pacing, sender));
+
+        Ok(ReplayWs { receiver })
+    }
+}
+
+async fn replay(mut lines: Lines<BufReader<File>>, pacing: Pacing, sender: Sender<Item>) {
+    let mut previous = None;
+
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => return,
+            Err(err) => {
+                let _ = sender.send(Err(GatewayError::from(err))).await;
+                return;
+            }
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let frame = match serde_json::from_str::<Frame>(&line) {
+            Ok(frame) => frame,
+            Err(err) => {
+                if sender.send(Err(GatewayError::from(err))).await.is_err() {
+                    return;
+                }
+                continue;
+            }
+        };
+
+        if frame.direction != Direction::Incoming {
+            continue;
+        }
+
+        if pacing == Pacing::RealTime {
+            if let Some(previous) = previous {
+                sleep(Duration::from_millis(
+                    frame.timestamp.saturating_sub(previous),
+                ))
+                .await;
+            }
+            previous = Some(frame.timestamp);
+        }
+
+        // Binary frames only ever come from MessagePack sessions
+        let event = Format::MessagePack.decode(frame.data);
+
+        let mut events = Vec::new();
+        match event {
+            Ok(ServerToClientEvent::Bulk { v }) => flatten_bulk(v, &mut events),
+            event => events.push(event),
+        }
+
+        for event in events {
+            if sender.send(event).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl Stream for ReplayWs {
+    type Item = Item;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::{FrameData, Recorder};
+    use futures_util::StreamExt;
+    use serde_json::json;
+    use tokio_tungstenite::tungstenite::Message;
+
+    /// Wait for the recorder to write the expected number of lines.
+    async fn read_lines(path: &Path, count: usize) -> Vec<String> {
+        for _ in 0..100 {
+            let content = tokio::fs::read_to_string(path).await.unwrap_or_default();
+            let lines: Vec<String> = content.lines().map(String::from).collect();
+            if lines.len() >= count {
+                return lines;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+        panic!("recording was not written");
+    }
+
+    #[tokio::test]
+    async fn recorded_session_is_replayed() {
+        let path = std::env::temp_dir().join(format!("revolt-replay-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let bulk = json!({
+            "type": "Bulk",
+            "v": [{ "type": "Authenticated" }, { "type": "Pong", "data": 1 }]
+        });
+        let message = json!({ "type": "Message", "_id": "M", "channel": "C", "author": "U" });
+        let binary = rmp_serde::to_vec_named(&message).unwrap();
+
+        let recorder = Recorder::create(&path).await.unwrap();
+        recorder.record(Direction::Incoming, &Message::Text(bulk.to_string()));
+        recorder.record(
+            Direction::Outgoing,
+            &Message::Text(r#"{"type":"Ping","data":1}"#.to_string()),
+        );
+        recorder.record(Direction::Incoming, &Message::Ping(Vec::new()));
+        recorder.record(Direction::Incoming, &Message::Binary(binary.clone()));
+        drop(recorder);
+
+        let lines = read_lines(&path, 3).await;
+        assert_eq!(lines.len(), 3, "control frames are not recorded");
+
+        // Content sits next to the other fields, tagged by its kind
+        let frames: Vec<Frame> = lines
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let first: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(first["direction"], "incoming");
+        assert_eq!(first["kind"], "text");
+        assert_eq!(first["data"], bulk.to_string());
+        assert_eq!(frames[1].direction, Direction::Outgoing);
+        assert_eq!(frames[2].data, FrameData::Binary(binary));
+
+        let mut replay = ReplayWs::open(&path, Pacing::AsFastAsPossible)
+            .await
+            .unwrap();
+        assert!(matches!(
+            replay.next().await,
+            Some(Ok(ServerToClientEvent::Authenticated))
+        ));
+        assert!(matches!(
+            replay.next().await,
+            Some(Ok(ServerToClientEvent::Pong { data: 1 }))
+        ));
+        assert!(matches!(
+            replay.next().await,
+            Some(Ok(ServerToClientEvent::Message { .. }))
+        ));
+        assert!(replay.next().await.is_none());
+
+        // Recording again appends to the file
+        let recorder = Recorder::create(&path).await.unwrap();
+        recorder.record(Direction::Incoming, &Message::Text(message.to_string()));
+        drop(recorder);
+        assert_eq!(read_lines(&path, 4).await.len(), 4);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,136 @@
+use revolt_models::event::{ErrorId, ServerToClientEvent};
+
+/// State of the connection to the events server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Opening the socket
+    Connecting,
+    /// Socket is open, waiting for the server to accept the token
+    Authenticating,
+    /// Token is accepted, waiting for the initial `Ready` payload
+    Authenticated,
+    /// Receiving events
+    Ready,
+    /// Connection was lost, waiting before reconnecting
+    Reconnecting { attempt: u32 },
+    /// Connection is closed for good
+    Closed { reason: CloseReason },
+}
+
+impl ConnectionState {
+    /// Whether the connection will never leave this state.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, ConnectionState::Closed { .. })
+    }
+}
+
+/// Why the connection was closed for good.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseReason {
+    /// Closed with [crate::RevoltWs::close] or by dropping it
+    Requested,
+    /// Server rejected the token
+    InvalidSession,
+    /// Account has to finish onboarding before it can connect
+    OnboardingNotFinished,
+}
+
+/// What the connection should do after an event was received.
+pub(crate) enum Transition {
+    /// Keep going, possibly in a new state
+    Continue(Option<ConnectionState>),
+    /// Server had a recoverable problem, start over with a new connection
+    Reconnect(String),
+    /// Server will not accept this connection, stop for good
+    Terminate(CloseReason),
+}
+
+/// Work out the effect of a server event on the connection state.
+pub(crate) fn transition(current: &ConnectionState, event: &ServerToClientEvent) -> Transition {
+    match event {
+        ServerToClientEvent::Authenticated => {
+            Transition::Continue(Some(ConnectionState::Authenticated))
+        }
+        ServerToClientEvent::Ready { .. } => Transition::Continue(Some(ConnectionState::Ready)),
+        ServerToClientEvent::Error { error } => match error {
+            ErrorId::InvalidSenssion => Transition::Terminate(CloseReason::InvalidSession),
+            ErrorId::OnboardingNotFinished => {
+                Transition::Terminate(CloseReason::OnboardingNotFinished)
+            }
+            // Token was already accepted, e.g. through the URL
+            ErrorId::AlreadyAuthenticated if *current == ConnectionState::Authenticating => {
+                Transition::Continue(Some(ConnectionState::Authenticated))
+            }
+            ErrorId::InternalError => Transition::Reconnect("internal server error".to_string()),
+            _ => Transition::Continue(None),
+        },
+        _ => Transition::Continue(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(error: ErrorId) -> ServerToClientEvent {
+        ServerToClientEvent::Error { error }
+    }
+
+    #[test]
+    fn rejected_sessions_are_terminal() {
+        for state in [ConnectionState::Authenticating, ConnectionState::Ready] {
+            assert!(matches!(
+                transition(&state, &error(ErrorId::InvalidSenssion)),
+                Transition::Terminate(CloseReason::InvalidSession)
+            ));
+            assert!(matches!(
+                transition(&state, &error(ErrorId::OnboardingNotFinished)),
+                Transition::Terminate(CloseReason::OnboardingNotFinished)
+            ));
+        }
+    }
+
+    #[test]
+    fn already_authenticated_only_counts_while_authenticating() {
+        assert!(matches!(
+            transition(
+                &ConnectionState::Authenticating,
+                &error(ErrorId::AlreadyAuthenticated)
+            ),
+            Transition::Continue(Some(ConnectionState::Authenticated))
+        ));
+        assert!(matches!(
+            transition(
+                &ConnectionState::Ready,
+                &error(ErrorId::AlreadyAuthenticated)
+            ),
+            Transition::Continue(None)
+        ));
+    }
+
+    #[test]
+    fn internal_error_reconnects() {
+        assert!(matches!(
+            transition(&ConnectionState::Ready, &error(ErrorId::InternalError)),
+            Transition::Reconnect(_)
+        ));
+    }
+
+    #[test]
+    fn handshake_events_advance_the_state() {
+        assert!(matches!(
+            transition(
+                &ConnectionState::Authenticating,
+                &ServerToClientEvent::Authenticated
+            ),
+            Transition::Continue(Some(ConnectionState::Authenticated))
+        ));
+        assert!(matches!(
+            transition(
+                &ConnectionState::Ready,
+                &ServerToClientEvent::Pong { data: 1 }
+            ),
+            Transition::Continue(None)
+        ));
+    }
+}
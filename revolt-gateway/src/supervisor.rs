@@ -0,0 +1,536 @@
+use crate::{
+    backoff::Backoff,
+    builder::RevoltWsBuilder,
+    connection::Config,
+    state::{CloseReason, ConnectionState},
+    GatewayError, RevoltWs,
+};
+use futures_util::{Stream, StreamExt};
+use revolt_models::event::{ClientToServerEvent, ServerToClientEvent};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, Weak,
+    },
+    time::Duration,
+};
+use tokio::{
+    select, spawn,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        oneshot, watch,
+    },
+    task::JoinHandle,
+    time::sleep,
+};
+
+type Item = Result<ServerToClientEvent, GatewayError>;
+
+/// Maximum number of events waiting to be read from the merged stream.
+///
+/// Once it is reached, events pile up in the queues of the individual connections.
+const MERGED_CAPACITY: usize = 1024;
+
+/// Bounds for the delay between restarts of a failed connection.
+const RESTART_DELAY: (Duration, Duration) = (Duration::from_secs(1), Duration::from_secs(60));
+
+/// Parts of the current connection of an account.
+#[derive(Debug, Clone)]
+struct Handle {
+    sender: Sender<ClientToServerEvent>,
+    state: watch::Receiver<ConnectionState>,
+}
+
+#[derive(Debug)]
+struct Account {
+    /// Tells the task apart from later ones added under the same key
+    id: u64,
+    handle: watch::Receiver<Handle>,
+    // Dropping it tells the task to close the connection
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+/// Accounts and the sending half of the merged stream.
+#[derive(Debug)]
+struct Shared<K> {
+    accounts: Mutex<HashMap<K, Account>>,
+    events: Sender<(K, Item)>,
+    next_id: AtomicU64,
+}
+
+/// Runs gateway connections for many accounts at once.
+///
+/// Every account gets its own [RevoltWs] which reconnects by itself.
+/// Connections that end anyway, e.g. because their task failed, are
+/// started again, independently of the other accounts. Accounts the
+/// server keeps rejecting are removed.
+///
+/// Events of all accounts are merged into one stream of
+/// `(account, event)` pairs. The stream waits for accounts to be added
+/// and does not end on its own. Use a [SupervisorHandle] to add and
+/// remove accounts while the stream is being read.
+#[derive(Debug)]
+pub struct Supervisor<K> {
+    handle: SupervisorHandle<K>,
+    receiver: Receiver<(K, Item)>,
+}
+
+/// Manages the accounts of a [Supervisor] from anywhere.
+///
+/// Accounts keep running while a handle is left, until they fail to
+/// deliver an event because the supervisor is gone.
+#[derive(Debug)]
+pub struct SupervisorHandle<K> {
+    shared: Arc<Shared<K>>,
+}
+
+impl<K> Supervisor<K>
+where
+    K: Clone + Eq + Hash + Send + 'static,
+{
+    pub fn new() -> Self {
+        let (events, receiver) = mpsc::channel(MERGED_CAPACITY);
+        let shared = Shared {
+            accounts: Mutex::new(HashMap::new()),
+            events,
+            next_id: AtomicU64::new(0),
+        };
+
+        Supervisor {
+            handle: SupervisorHandle {
+                shared: Arc::new(shared),
+            },
+            receiver,
+        }
+    }
+
+    /// Handle to manage the accounts while the stream is borrowed.
+    pub fn handle(&self) -> SupervisorHandle<K> {
+        self.handle.clone()
+    }
+
+    /// See [SupervisorHandle::add].
+    pub async fn add(&self, account: K, builder: RevoltWsBuilder) -> Result<(), GatewayError> {
+        self.handle.add(account, builder).await
+    }
+
+    /// See [SupervisorHandle::remove].
+    pub async fn remove(&self, account: &K) -> bool {
+        self.handle.remove(account).await
+    }
+
+    /// See [SupervisorHandle::close].
+    pub async fn close(&self) {
+        self.handle.close().await
+    }
+
+    pub fn contains(&self, account: &K) -> bool {
+        self.handle.contains(account)
+    }
+
+    pub fn accounts(&self) -> Vec<K> {
+        self.handle.accounts()
+    }
+
+    /// See [SupervisorHandle::send].
+    pub async fn send(&self, account: &K, event: ClientToServerEvent) -> Result<(), GatewayError> {
+        self.handle.send(account, event).await
+    }
+
+    /// See [SupervisorHandle::state].
+    pub fn state(&self, account: &K) -> Option<ConnectionState> {
+        self.handle.state(account)
+    }
+}
+
+impl<K> Default for Supervisor<K>
+where
+    K: Clone + Eq + Hash + Send + 'static,
+{
+    fn default() -> Self {
+        Supervisor::new()
+    }
+}
+
+impl<K> SupervisorHandle<K>
+where
+    K: Clone + Eq + Hash + Send + 'static,
+{
+    /// Connect an account and merge its events into the stream.
+    ///
+    /// An account already known by this key is closed first.
+    /// Errors of the first connection attempt are returned, later ones
+    /// are yielded from the stream.
+    pub async fn add(&self, account: K, builder: RevoltWsBuilder) -> Result<(), GatewayError> {
+        self.remove(&account).await;
+
+        let ws = builder.clone().connect().await?;
+        let (handle_sender, handle) = watch::channel(Handle::of(&ws));
+        let (stop, stop_receiver) = oneshot::channel();
+        let id = self.shared.next_id.fetch_add(1, Ordering::Relaxed);
+
+        let slot = Slot {
+            account: account.clone(),
+            id,
+            shared: Arc::downgrade(&self.shared),
+        };
+        let task = spawn(supervise(
+            slot,
+            builder,
+            ws,
+            self.shared.events.clone(),
+            handle_sender,
+            stop_receiver,
+        ));
+
+        let replaced = self.shared.lock().insert(
+            account,
+            Account {
+                id,
+                handle,
+                stop,
+                task,
+            },
+        );
+        // One added concurrently under the same key stops once dropped
+        drop(replaced);
+        Ok(())
+    }
+
+    /// Gracefully close the connection of an account.
+    ///
+    /// Returns `false` if the account is not known.
+    pub async fn remove(&self, account: &K) -> bool {
+        let removed = self.shared.lock().remove(account);
+        match removed {
+            Some(account) => {
+                account.stop().await;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Gracefully close all connections.
+    pub async fn close(&self) {
+        let accounts: Vec<Account> = self.shared.lock().drain().map(|(_, a)| a).collect();
+        for account in accounts {
+            account.stop().await;
+        }
+    }
+
+    pub fn contains(&self, account: &K) -> bool {
+        self.shared.lock().contains_key(account)
+    }
+
+    pub fn accounts(&self) -> Vec<K> {
+        self.shared.lock().keys().cloned().collect()
+    }
+
+    /// Send an event through the connection of an account.
+    pub async fn send(&self, account: &K, event: ClientToServerEvent) -> Result<(), GatewayError> {
+        let sender = match self.shared.lock().get(account) {
+            Some(Account { handle, .. }) => handle.borrow().sender.clone(),
+            None => return Err(GatewayError::Closed),
+        };
+
+        sender.send(event).await.map_err(|_| GatewayError::Closed)
+    }
+
+    /// Current state of the connection of an account.
+    pub fn state(&self, account: &K) -> Option<ConnectionState> {
+        self.shared
+            .lock()
+            .get(account)
+            .map(|Account { handle, .. }| handle.borrow().state.borrow().clone())
+    }
+}
+
+impl<K> Clone for SupervisorHandle<K> {
+    fn clone(&self) -> Self {
+        SupervisorHandle {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<K> Shared<K> {
+    fn lock(&self) -> MutexGuard<'_, HashMap<K, Account>> {
+        // Only map operations run under the lock, a panic can't leave it half-updated
+        self.accounts.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Account {
+    async fn stop(self) {
+        // Task is already gone if this fails
+        let _ = self.stop.send(());
+        let _ = self.task.await;
+    }
+}
+
+impl Handle {
+    fn of(ws: &RevoltWs) -> Self {
+        Handle {
+            sender: ws.client_event_sender.clone(),
+            state: ws.state.clone(),
+        }
+    }
+}
+
+/// Where the task of an account is registered.
+struct Slot<K> {
+    account: K,
+    id: u64,
+    shared: Weak<Shared<K>>,
+}
+
+impl<K: Eq + Hash> Slot<K> {
+    /// Remove the account, unless it was replaced in the meantime.
+    fn forget(&self) {
+        let shared = match self.shared.upgrade() {
+            Some(shared) => shared,
+            None => return,
+        };
+
+        let mut accounts = shared.lock();
+        if accounts.get(&self.account).map(|a| a.id) == Some(self.id) {
+            accounts.remove(&self.account);
+        }
+    }
+}
+
+/// Forward the events of an account and restart its connection whenever it ends.
+async fn supervise<K: Clone + Eq + Hash>(
+    slot: Slot<K>,
+    builder: RevoltWsBuilder,
+    mut ws: RevoltWs,
+    events: Sender<(K, Item)>,
+    handle: watch::Sender<Handle>,
+    mut stop: oneshot::Receiver<()>,
+) {
+    let account = slot.account.clone();
+    let (initial, max) = RESTART_DELAY;
+    let mut backoff = Backoff::new(initial, max);
+
+    loop {
+        loop {
+            select! {
+                event = ws.next() => match event {
+                    Some(event) => {
+                        if matches!(event, Ok(ServerToClientEvent::Ready { .. })) {
+                            backoff.reset();
+                        }
+
+                        select! {
+                            result = events.send((account.clone(), event)) => {
+                                // Supervisor is gone
+                                if result.is_err() {
+                                    let _ = ws.close().await;
+                                    return;
+                                }
+                            }
+                            _ = &mut stop => {
+                                let _ = ws.close().await;
+                                return;
+                            }
+                        }
+                    }
+                    None => break,
+                },
+                _ = &mut stop => {
+                    let _ = ws.close().await;
+                    return;
+                }
+            }
+        }
+
+        // Stream only ends by itself if the connection was terminated or its task failed
+        let (config, result) = match ws.shutdown().await {
+            Ok(config) => (Some(config), Ok(())),
+            Err(err) => (None, Err(err)),
+        };
+        match ws.state() {
+            ConnectionState::Closed {
+                reason: CloseReason::InvalidSession | CloseReason::OnboardingNotFinished,
+            } => {
+                // Server will keep rejecting the account, no point in restarting
+                slot.forget();
+                let _ = events.send((account, Err(GatewayError::Closed))).await;
+                return;
+            }
+            _ => {
+                if let Err(err) = result {
+                    if events.send((account.clone(), Err(err))).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+
+        ws = loop {
+            select! {
+                _ = sleep(backoff.next_delay()) => {}
+                _ = &mut stop => return,
+            }
+
+            let result = select! {
+                result = restart(&builder, config.as_ref()) => result,
+                _ = &mut stop => return,
+            };
+
+            match result {
+                Ok(ws) => break ws,
+                Err(err) => {
+                    if events.send((account.clone(), Err(err))).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        };
+
+        handle.send_replace(Handle::of(&ws));
+    }
+}
+
+/// Connect again, continuing where the previous connection left off.
+///
+/// Its config keeps the recorder writing to the same file and the channels
+/// the backfill has seen. Only if its task failed is the builder used again.
+async fn restart(
+    builder: &RevoltWsBuilder,
+    config: Option<&Config>,
+) -> Result<RevoltWs, GatewayError> {
+    match config {
+        Some(config) => {
+            let mut config = config.clone();
+            config.backoff.reset();
+            RevoltWs::connect_with_config(config).await
+        }
+        None => builder.clone().connect().await,
+    }
+}
+
+impl<K: Unpin> Stream for Supervisor<K> {
+    type Item = (K, Item);
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::SinkExt;
+    use serde_json::{json, Value};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+    /// Events server greeting every account with a typing event in the channel named
+    /// after its token, tokens starting with `rejected` are turned away.
+    async fn serve() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                spawn(async move {
+                    let mut ws = accept_async(stream).await.unwrap();
+                    let token = match ws.next().await {
+                        Some(Ok(Message::Text(text))) => {
+                            let event: Value = serde_json::from_str(&text).unwrap();
+                            event["token"].as_str().unwrap().to_string()
+                        }
+                        _ => return,
+                    };
+
+                    let events = if token.starts_with("rejected") {
+                        vec![json!({ "type": "Error", "error": { "type": "InvalidSenssion" } })]
+                    } else {
+                        vec![
+                            json!({ "type": "Authenticated" }),
+                            json!({ "type": "ChannelStartTyping", "id": token, "user": "U" }),
+                        ]
+                    };
+                    for event in events {
+                        if ws.send(Message::Text(event.to_string())).await.is_err() {
+                            return;
+                        }
+                    }
+
+                    // Keep the connection open until the client is done
+                    while let Some(Ok(_)) = ws.next().await {}
+                });
+            }
+        });
+
+        url
+    }
+
+    fn builder(url: &str, token: &str) -> RevoltWsBuilder {
+        RevoltWsBuilder::new(token).url(url)
+    }
+
+    #[tokio::test]
+    async fn merges_added_accounts_until_removed() {
+        let url = serve().await;
+        let mut supervisor = Supervisor::new();
+        let handle = supervisor.handle();
+
+        // Handles work from other tasks while the stream is borrowed
+        let adding = spawn({
+            let handle = handle.clone();
+            let builder = builder(&url, "a");
+            async move { handle.add("a", builder).await }
+        });
+        adding.await.unwrap().unwrap();
+        supervisor.add("b", builder(&url, "b")).await.unwrap();
+
+        let mut typing = Vec::new();
+        while typing.len() < 2 {
+            let (account, event) = supervisor.next().await.unwrap();
+            if let Ok(ServerToClientEvent::ChannelStartTyping { id, .. }) = event {
+                assert_eq!(id, account);
+                typing.push(account);
+            }
+        }
+        typing.sort();
+        assert_eq!(typing, ["a", "b"]);
+
+        assert!(handle.remove(&"a").await);
+        assert!(!handle.remove(&"a").await);
+        assert_eq!(supervisor.accounts(), ["b"]);
+        assert!(supervisor.state(&"a").is_none());
+
+        supervisor.close().await;
+        assert!(supervisor.accounts().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejected_account_is_removed() {
+        let url = serve().await;
+        let mut supervisor = Supervisor::new();
+        supervisor
+            .add("rejected", builder(&url, "rejected"))
+            .await
+            .unwrap();
+
+        // The rejection itself comes first
+        loop {
+            let (account, event) = supervisor.next().await.unwrap();
+            assert_eq!(account, "rejected");
+            if event.is_err() {
+                assert!(matches!(event, Err(GatewayError::Closed)));
+                break;
+            }
+        }
+        assert!(!supervisor.contains(&"rejected"));
+    }
+}
@@ -0,0 +1,54 @@
+use revolt_models::event::ClientToServerEvent;
+use std::time::Duration;
+use tokio::{
+    select, spawn,
+    sync::{mpsc::Sender, oneshot},
+    time::interval,
+};
+
+/// How often the typing indicator is renewed, clients hide it after a few seconds.
+const TYPING_REFRESH: Duration = Duration::from_secs(3);
+
+/// Keeps the typing indicator up in a channel until dropped.
+///
+/// Created with [crate::RevoltWs::typing].
+#[derive(Debug)]
+#[must_use = "typing stops as soon as the guard is dropped"]
+pub struct TypingGuard {
+    // Dropping it tells the refresh task to stop
+    _stop: oneshot::Sender<()>,
+}
+
+impl TypingGuard {
+    pub(crate) fn start(sender: Sender<ClientToServerEvent>, channel: String) -> Self {
+        let (stop_sender, mut stop_receiver) = oneshot::channel::<()>();
+
+        spawn(async move {
+            let mut refresh = interval(TYPING_REFRESH);
+
+            loop {
+                select! {
+                    _ = refresh.tick() => {
+                        let event = ClientToServerEvent::BeginTyping {
+                            channel: channel.clone(),
+                        };
+                        if sender.send(event).await.is_err() {
+                            return;
+                        }
+                    }
+                    _ = &mut stop_receiver => break,
+                }
+            }
+
+            // Connection may be gone already, then there is nobody to tell
+            let _ = sender
+                .send(ClientToServerEvent::EndTyping { channel })
+                .await;
+        });
+
+        TypingGuard { _stop: stop_sender }
+    }
+
+    /// Stop typing right away, same as dropping the guard.
+    pub fn stop(self) {}
+}
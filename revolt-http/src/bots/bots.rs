@@ -7,31 +7,17 @@ use revolt_models::{
 impl RevoltHttp {
     /// Create a new Revolt bot.
     pub async fn create_bot(&self, payload: CreateBotPayload) -> Result<Bot> {
-        Ok(self
-            .client
-            .post(ep!(self, "/bots/create"))
+        self.request(Route::CreateBot)
             .json(&payload)
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+            .response()
+            .await
     }
 
     /// Fetch details of a public (or owned) bot by its id.
     pub async fn fetch_public_bot(&self, id: impl Into<String>) -> Result<PublicBot> {
-        Ok(self
-            .client
-            .get(ep!(self, "/bots/{}/invite", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::FetchPublicBot { bot_id: id.into() })
+            .response()
+            .await
     }
 
     /// Invite a bot to a server or group by its id.
@@ -40,69 +26,38 @@ impl RevoltHttp {
         bot_id: impl Into<String>,
         payload: InviteBotPayload,
     ) -> Result<()> {
-        self.client
-            .post(ep!(self, "/bots/{}/invite", bot_id.into()))
-            .json(&payload)
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?;
-        Ok(())
+        self.request(Route::InviteBot {
+            bot_id: bot_id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
     }
 
     /// Fetch details of a bot you own by its id.
     pub async fn fetch_bot(&self, id: impl Into<String>) -> Result<OwnedBot> {
-        Ok(self
-            .client
-            .get(ep!(self, "/bots/{}/invite", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::FetchBot { bot_id: id.into() })
+            .response()
+            .await
     }
 
     /// Delete a bot by its id.
     pub async fn delete_bot(&self, id: impl Into<String>) -> Result<()> {
-        self.client
-            .delete(ep!(self, "/bots/{}", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?;
-        Ok(())
+        self.request(Route::DeleteBot { bot_id: id.into() })
+            .response()
+            .await
     }
 
     /// Edit bot details by its id.
     pub async fn edit_bot(&self, id: impl Into<String>, payload: EditBotPayload) -> Result<Bot> {
-        Ok(self
-            .client
-            .patch(ep!(self, "/bots/{}", id.into()))
+        self.request(Route::EditBot { bot_id: id.into() })
             .json(&payload)
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+            .response()
+            .await
     }
 
     /// Fetch all of the bots that you have control over.
     pub async fn fetch_owned_bots(&self) -> Result<OwnedBots> {
-        Ok(self
-            .client
-            .get(ep!(self, "/bots/@me"))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::FetchOwnedBots).response().await
     }
 }
@@ -0,0 +1,178 @@
+use crate::{Result, RetryPolicy, RevoltHttp, RevoltHttpError, Transport};
+use reqwest::{header::HeaderMap, Client, Proxy};
+use revolt_models::authentication::Authentication;
+use std::{sync::Arc, time::Duration};
+
+/// Builder for a [RevoltHttp] client.
+///
+/// Either configures a new [reqwest::Client] or uses an existing one,
+/// e.g. to share its connection pool with the rest of a service.
+#[derive(Debug, Clone)]
+pub struct RevoltHttpBuilder {
+    authentication: Authentication,
+    base_url: String,
+    client: Option<Client>,
+    transport: Option<Arc<dyn Transport>>,
+    retry_policy: RetryPolicy,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    user_agent: Option<String>,
+    proxies: Vec<Proxy>,
+    no_proxy: bool,
+    headers: HeaderMap,
+    pool_max_idle_per_host: Option<usize>,
+}
+
+impl RevoltHttpBuilder {
+    pub fn new(authentication: Authentication) -> Self {
+        RevoltHttpBuilder {
+            authentication,
+            base_url: "https://api.revolt.chat".to_string(),
+            client: None,
+            transport: None,
+            retry_policy: RetryPolicy::default(),
+            connect_timeout: None,
+            timeout: None,
+            user_agent: None,
+            proxies: Vec::new(),
+            no_proxy: false,
+            headers: HeaderMap::new(),
+            pool_max_idle_per_host: None,
+        }
+    }
+
+    /// API root, `https://api.revolt.chat` by default.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Use an existing client instead of configuring a new one.
+    ///
+    /// Timeouts, user agent, proxies, default headers and pool size are part
+    /// of the client then, [RevoltHttpBuilder::build] fails if any of them
+    /// is set on this builder too.
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Send requests through another transport instead of the [reqwest::Client].
+    ///
+    /// Requests are still built by the client, but it does not send them.
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Which failed requests to send again, see [RetryPolicy::new] for the default.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// How long to wait for a connection to be established, no limit by default.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// How long a request may take from connecting until the response
+    /// body has been read, no limit by default.
+    ///
+    /// Applies to every attempt of a request on its own.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// `User-Agent` header to send, `revolt.rs/<version>` by default.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Send requests through a proxy, may be called multiple times.
+    ///
+    /// Proxies from the `HTTP_PROXY` and `HTTPS_PROXY` environment variables
+    /// are used if none is set. SOCKS proxies need the `socks` feature.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Ignore proxies from the environment.
+    pub fn no_proxy(mut self) -> Self {
+        self.no_proxy = true;
+        self
+    }
+
+    /// Headers to send with every request.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Maximum number of idle connections kept open per host, no limit by default.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Fails if the client can't be created, e.g. because no TLS backend could be initialized,
+    /// or if client options are set next to an existing [RevoltHttpBuilder::client].
+    pub fn build(self) -> Result<RevoltHttp> {
+        let client = match self.client {
+            Some(client) => {
+                let configured = self.connect_timeout.is_some()
+                    || self.timeout.is_some()
+                    || self.user_agent.is_some()
+                    || !self.proxies.is_empty()
+                    || self.no_proxy
+                    || !self.headers.is_empty()
+                    || self.pool_max_idle_per_host.is_some();
+                if configured {
+                    return Err(RevoltHttpError::ClientOptionsIgnored);
+                }
+
+                client
+            }
+            None => {
+                let user_agent = self.user_agent.unwrap_or_else(|| {
+                    concat!("revolt.rs/", env!("CARGO_PKG_VERSION")).to_string()
+                });
+                let mut builder = Client::builder()
+                    .user_agent(user_agent)
+                    .default_headers(self.headers);
+
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if self.no_proxy {
+                    builder = builder.no_proxy();
+                }
+                for proxy in self.proxies {
+                    builder = builder.proxy(proxy);
+                }
+                if let Some(max) = self.pool_max_idle_per_host {
+                    builder = builder.pool_max_idle_per_host(max);
+                }
+
+                builder.build()?
+            }
+        };
+
+        let transport = self.transport.unwrap_or_else(|| Arc::new(client.clone()));
+
+        Ok(RevoltHttp::with_client(
+            self.authentication,
+            self.base_url,
+            client,
+            transport,
+            self.retry_policy,
+        ))
+    }
+}
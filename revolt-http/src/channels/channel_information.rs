@@ -4,28 +4,20 @@ use revolt_models::{channel::Channel, payload::EditChannelPayload};
 impl RevoltHttp {
     /// Fetch channel by its ID.
     pub async fn fetch_channel(&self, id: impl Into<String>) -> Result<Channel> {
-        Ok(self
-            .client
-            .get(ep!(self, "/channels/{}", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::FetchChannel {
+            channel_id: id.into(),
+        })
+        .response()
+        .await
     }
 
     /// Deletes a server channel, leaves a group or closes a group.
     pub async fn close_channel(&self, id: impl Into<String>) -> Result<()> {
-        self.client
-            .delete(ep!(self, "/channels/{}", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?;
-        Ok(())
+        self.request(Route::CloseChannel {
+            channel_id: id.into(),
+        })
+        .response()
+        .await
     }
 
     /// Edit a channel object by its id.
@@ -34,16 +26,11 @@ impl RevoltHttp {
         id: impl Into<String>,
         payload: EditChannelPayload,
     ) -> Result<Channel> {
-        Ok(self
-            .client
-            .patch(ep!(self, "/channels/{}", id.into()))
-            .auth(&self.authentication)
-            .json(&payload)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::EditChannel {
+            channel_id: id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
     }
 }
@@ -7,15 +7,10 @@ impl RevoltHttp {
     ///
     /// Channel must be a [Channel::TextChannel].
     pub async fn create_invite(&self, id: impl Into<String>) -> Result<Invite> {
-        Ok(self
-            .client
-            .post(ep!(self, "/channels/{}/invites", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::CreateInvite {
+            channel_id: id.into(),
+        })
+        .response()
+        .await
     }
 }
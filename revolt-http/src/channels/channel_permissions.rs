@@ -14,22 +14,13 @@ impl RevoltHttp {
         role_id: impl Into<String>,
         payload: SetRolePermissionPayload,
     ) -> Result<Channel> {
-        Ok(self
-            .client
-            .put(ep!(
-                self,
-                "/channels/{}/permissions/{}",
-                channel_id.into(),
-                role_id.into()
-            ))
-            .auth(&self.authentication)
-            .json(&payload)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::SetRoleChannelPermissions {
+            channel_id: channel_id.into(),
+            role_id: role_id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
     }
 
     /// Sets permissions for the specified role in this channel.
@@ -40,20 +31,11 @@ impl RevoltHttp {
         channel_id: impl Into<String>,
         payload: SetDefaultPermissionPayload,
     ) -> Result<Channel> {
-        Ok(self
-            .client
-            .put(ep!(
-                self,
-                "/channels/{}/permissions/default",
-                channel_id.into(),
-            ))
-            .auth(&self.authentication)
-            .json(&payload)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::SetDefaultChannelPermissions {
+            channel_id: channel_id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
     }
 }
@@ -4,31 +4,19 @@ use revolt_models::{channel::Channel, payload::CreateGroupPayload, user::User};
 impl RevoltHttp {
     /// Retrieves all users who are part of this group.
     pub async fn fetch_group_members(&self, id: impl Into<String>) -> Result<Vec<User>> {
-        Ok(self
-            .client
-            .get(ep!(self, "/channels/{}/members", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::FetchGroupMembers {
+            group_id: id.into(),
+        })
+        .response()
+        .await
     }
 
     /// Create a new group channel.
     pub async fn create_group(&self, payload: CreateGroupPayload) -> Result<Channel> {
-        Ok(self
-            .client
-            .post(ep!(self, "/channels/create"))
+        self.request(Route::CreateGroup)
             .json(&payload)
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+            .response()
+            .await
     }
 
     /// Adds another user to the group.
@@ -37,21 +25,12 @@ impl RevoltHttp {
         group_id: impl Into<String>,
         member_id: impl Into<String>,
     ) -> Result<()> {
-        self.client
-            .put(ep!(
-                self,
-                "/channels/{}/recipients/{}",
-                group_id.into(),
-                member_id.into()
-            ))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?;
-        Ok(())
+        self.request(Route::AddMemberToGroup {
+            group_id: group_id.into(),
+            member_id: member_id.into(),
+        })
+        .response()
+        .await
     }
 
     /// Removes a user from the group.
@@ -60,20 +39,11 @@ impl RevoltHttp {
         group_id: impl Into<String>,
         member_id: impl Into<String>,
     ) -> Result<()> {
-        self.client
-            .delete(ep!(
-                self,
-                "/channels/{}/recipients/{}",
-                group_id.into(),
-                member_id.into()
-            ))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?;
-        Ok(())
+        self.request(Route::RemoveMemberFromGroup {
+            group_id: group_id.into(),
+            member_id: member_id.into(),
+        })
+        .response()
+        .await
     }
 }
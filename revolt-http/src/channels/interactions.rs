@@ -10,20 +10,13 @@ impl RevoltHttp {
         message_id: impl Into<String>,
         emoji: impl Into<String>,
     ) -> Result<()> {
-        self.client
-            .put(ep!(
-                self,
-                "/channels/{}/messages/{}/reactions/{}",
-                channel_id.into(),
-                message_id.into(),
-                emoji.into()
-            ))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?;
-        Ok(())
+        self.request(Route::AddReactionToMessage {
+            channel_id: channel_id.into(),
+            message_id: message_id.into(),
+            emoji: emoji.into(),
+        })
+        .response()
+        .await
     }
 
     /// Remove your own, someone else's or all of a given reaction.
@@ -36,21 +29,14 @@ impl RevoltHttp {
         emoji: impl Into<String>,
         payload: RemoveReactionToMessagePayload,
     ) -> Result<()> {
-        self.client
-            .delete(ep!(
-                self,
-                "/channels/{}/messages/{}/reactions/{}",
-                channel_id.into(),
-                message_id.into(),
-                emoji.into()
-            ))
-            .query(&payload)
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?;
-        Ok(())
+        self.request(Route::RemoveReactionToMessage {
+            channel_id: channel_id.into(),
+            message_id: message_id.into(),
+            emoji: emoji.into(),
+        })
+        .query(&payload)
+        .response()
+        .await
     }
 
     /// Remove your own, someone else's or all of a given reaction.
@@ -61,18 +47,11 @@ impl RevoltHttp {
         channel_id: impl Into<String>,
         message_id: impl Into<String>,
     ) -> Result<()> {
-        self.client
-            .delete(ep!(
-                self,
-                "/channels/{}/messages/{}/reactions",
-                channel_id.into(),
-                message_id.into(),
-            ))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?;
-        Ok(())
+        self.request(Route::RemoveAllReactionsFromMessage {
+            channel_id: channel_id.into(),
+            message_id: message_id.into(),
+        })
+        .response()
+        .await
     }
 }
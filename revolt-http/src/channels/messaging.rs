@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::{prelude::*, retry::IDEMPOTENCY_KEY};
 use revolt_models::{
     message::{BulkMessageResponse, Message},
     payload::{
@@ -14,19 +14,12 @@ impl RevoltHttp {
         channel_id: impl Into<String>,
         message_id: impl Into<String>,
     ) -> Result<()> {
-        self.client
-            .put(ep!(
-                self,
-                "/channels/{}/ack/{}",
-                channel_id.into(),
-                message_id.into()
-            ))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?;
-        Ok(())
+        self.request(Route::AcknowledgeMessage {
+            channel_id: channel_id.into(),
+            message_id: message_id.into(),
+        })
+        .response()
+        .await
     }
 
     /// Fetch multiple messages.
@@ -35,17 +28,12 @@ impl RevoltHttp {
         channel_id: impl Into<String>,
         payload: FetchMessagesPayload,
     ) -> Result<BulkMessageResponse> {
-        Ok(self
-            .client
-            .get(ep!(self, "/channels/{}/messages", channel_id.into()))
-            .auth(&self.authentication)
-            .query(&payload)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::FetchMessages {
+            channel_id: channel_id.into(),
+        })
+        .query(&payload)
+        .response()
+        .await
     }
 
     /// Send a message to a given channel.
@@ -54,17 +42,17 @@ impl RevoltHttp {
         channel_id: impl Into<String>,
         payload: SendMessagePayload,
     ) -> Result<Message> {
-        Ok(self
-            .client
-            .post(ep!(self, "/channels/{}/messages", channel_id.into()))
-            .auth(&self.authentication)
-            .json(&payload)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        let mut request = self
+            .request(Route::SendMessage {
+                channel_id: channel_id.into(),
+            })
+            .json(&payload);
+
+        if let Some(nonce) = &payload.nonce {
+            request = request.header(IDEMPOTENCY_KEY, nonce);
+        }
+
+        request.response().await
     }
 
     /// Search for messages within the given parameters.
@@ -73,17 +61,12 @@ impl RevoltHttp {
         channel_id: impl Into<String>,
         payload: SearchForMessagesPayload,
     ) -> Result<Message> {
-        Ok(self
-            .client
-            .post(ep!(self, "/channels/{}/messages/search", channel_id.into()))
-            .auth(&self.authentication)
-            .json(&payload)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::SearchForMessages {
+            channel_id: channel_id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
     }
 
     /// Retrieves a message by its ID.
@@ -92,21 +75,12 @@ impl RevoltHttp {
         channel_id: impl Into<String>,
         message_id: impl Into<String>,
     ) -> Result<Message> {
-        Ok(self
-            .client
-            .get(ep!(
-                self,
-                "/channels/{}/messages/{}",
-                channel_id.into(),
-                message_id.into()
-            ))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::FetchMessage {
+            channel_id: channel_id.into(),
+            message_id: message_id.into(),
+        })
+        .response()
+        .await
     }
 
     /// Delete a message you've sent or one you have permission to delete.
@@ -115,19 +89,12 @@ impl RevoltHttp {
         channel_id: impl Into<String>,
         message_id: impl Into<String>,
     ) -> Result<()> {
-        self.client
-            .delete(ep!(
-                self,
-                "/channels/{}/messages/{}",
-                channel_id.into(),
-                message_id.into()
-            ))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?;
-        Ok(())
+        self.request(Route::DeleteMessage {
+            channel_id: channel_id.into(),
+            message_id: message_id.into(),
+        })
+        .response()
+        .await
     }
 
     pub async fn edit_message(
@@ -136,22 +103,13 @@ impl RevoltHttp {
         message_id: impl Into<String>,
         payload: EditMessagePayload,
     ) -> Result<Message> {
-        Ok(self
-            .client
-            .patch(ep!(
-                self,
-                "/channels/{}/messages/{}",
-                channel_id.into(),
-                message_id.into()
-            ))
-            .auth(&self.authentication)
-            .json(&payload)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::EditMessage {
+            channel_id: channel_id.into(),
+            message_id: message_id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
     }
 
     /// Delete multiple messages you've sent or one you have permission to delete.
@@ -164,14 +122,11 @@ impl RevoltHttp {
         channel_id: impl Into<String>,
         payload: BulkDeleteMessagesPayload,
     ) -> Result<()> {
-        self.client
-            .delete(ep!(self, "/channels/{}/messages/bulk", channel_id.into(),))
-            .auth(&self.authentication)
-            .json(&payload)
-            .send()
-            .await?
-            .process_error()
-            .await?;
-        Ok(())
+        self.request(Route::BulkDeleteMessages {
+            channel_id: channel_id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
     }
 }
@@ -0,0 +1,49 @@
+use crate::prelude::*;
+use revolt_models::{emoji::Emoji, payload::CreateEmojiPayload};
+
+impl RevoltHttp {
+    /// Fetch an emoji by its id.
+    pub async fn fetch_emoji(&self, id: impl Into<String>) -> Result<Emoji> {
+        self.request(Route::FetchEmoji {
+            emoji_id: id.into(),
+        })
+        .response()
+        .await
+    }
+
+    /// Create an emoji by its Autumn upload id.
+    ///
+    /// Requires [Permission::ManageCustomisation] in the parent server.
+    pub async fn create_emoji(
+        &self,
+        id: impl Into<String>,
+        payload: CreateEmojiPayload,
+    ) -> Result<Emoji> {
+        self.request(Route::CreateEmoji {
+            emoji_id: id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
+    }
+
+    /// Delete an emoji by its id.
+    ///
+    /// Requires [Permission::ManageCustomisation] unless you created the emoji.
+    pub async fn delete_emoji(&self, id: impl Into<String>) -> Result<()> {
+        self.request(Route::DeleteEmoji {
+            emoji_id: id.into(),
+        })
+        .response()
+        .await
+    }
+
+    /// Fetch all emoji on a server.
+    pub async fn fetch_server_emojis(&self, server_id: impl Into<String>) -> Result<Vec<Emoji>> {
+        self.request(Route::FetchServerEmojis {
+            server_id: server_id.into(),
+        })
+        .response()
+        .await
+    }
+}
@@ -0,0 +1,60 @@
+use crate::ratelimit;
+use reqwest::{header::HeaderMap, Response, StatusCode};
+use revolt_models::ApiError;
+use std::{fmt, time::Duration};
+
+/// Unsuccessful response from the API.
+#[derive(Debug, Clone)]
+pub struct ApiErrorResponse {
+    /// Error reported by the API
+    ///
+    /// `None` if the body is no API error, e.g. an HTML error page of a proxy.
+    pub error: Option<ApiError>,
+    /// Response status
+    pub status: StatusCode,
+    /// Response headers
+    pub headers: HeaderMap,
+    /// Response body as received
+    pub body: String,
+    /// Method and path of the request, e.g. `GET /users/@me`
+    pub route: String,
+}
+
+impl ApiErrorResponse {
+    pub(crate) async fn read(route: String, response: Response) -> Self {
+        let status = response.status();
+        let headers = response.headers().clone();
+        // The status still tells what happened if the body can't be read
+        let body = response.text().await.unwrap_or_default();
+
+        let error = if status == StatusCode::UNAUTHORIZED {
+            // API returns HTML instead of parseable JSON here
+            Some(ApiError::Unauthenticated)
+        } else {
+            serde_json::from_str(&body).ok()
+        };
+
+        ApiErrorResponse {
+            error,
+            status,
+            headers,
+            body,
+            route,
+        }
+    }
+
+    /// How long the server asked to wait before trying again.
+    pub fn retry_after(&self) -> Option<Duration> {
+        ratelimit::retry_after(&self.headers, &self.body)
+    }
+}
+
+impl fmt::Display for ApiErrorResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} failed with {}", self.route, self.status)?;
+        match &self.error {
+            Some(error) => write!(f, ": {:?}", error),
+            None => Ok(()),
+        }
+    }
+}
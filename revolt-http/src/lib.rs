@@ -1,17 +1,35 @@
 mod bots;
+mod builder;
 mod channels;
+mod customisation;
+mod error;
+mod ratelimit;
+mod request;
+mod retry;
 mod revolt;
+mod route;
 mod servers;
+mod transport;
 mod users;
 
-use reqwest::{Client, StatusCode};
+pub use builder::RevoltHttpBuilder;
+pub use error::ApiErrorResponse;
+pub use ratelimit::RateLimitBucket;
+pub use retry::{RetryNonIdempotent, RetryPolicy};
+pub use transport::{Transport, TransportError};
+
+use ratelimit::RateLimiter;
+use request::ApiRequest;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use revolt_models::{authentication::Authentication, ApiError};
-use std::result::Result as StdResult;
+use route::Route;
+use std::{result::Result as StdResult, sync::Arc, time::Duration};
+use tokio::time::sleep;
 
 type Result<T> = StdResult<T, RevoltHttpError>;
 
 pub(crate) mod prelude {
-    pub(crate) use crate::{ep, RequestBuilderExt, ResponseExt, Result, RevoltHttp};
+    pub(crate) use crate::{route::Route, Result, RevoltHttp};
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -22,70 +40,40 @@ pub enum RevoltHttpError {
     #[error("Error while processing an HTTP request: {0}")]
     HttpRequest(#[from] reqwest::Error),
 
-    #[error("Error returned from API")]
-    Api(ApiError),
-}
-
-#[macro_export]
-macro_rules! ep {
-    ($self:ident, $ep:literal, $($args:tt)*) => {
-        format!(concat!("{}", $ep), $self.base_url, $($args)*)
-    };
-
-    ($self:ident, $ep:literal) => {
-        format!(concat!("{}", $ep), $self.base_url)
-    };
+    #[error("Error while sending an HTTP request: {0}")]
+    Transport(#[from] TransportError),
 
-    (api_root = $api_root:expr, $ep:literal $($args:tt)*) => {
-        format!(concat!("{}", $ep), $api_root, $($args)*)
-    };
-}
+    #[error("Client options were set on a builder that was given a client")]
+    ClientOptionsIgnored,
 
-trait RequestBuilderExt {
-    fn auth(self, authentication: &Authentication) -> Self;
+    #[error("Error returned from API: {0}")]
+    Api(Box<ApiErrorResponse>),
 }
 
-impl RequestBuilderExt for reqwest::RequestBuilder {
-    fn auth(self, authentication: &Authentication) -> Self {
-        self.header(authentication.header_key(), authentication.value())
+impl RevoltHttpError {
+    /// Error reported by the API, if that is what went wrong.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            RevoltHttpError::Api(response) => response.error.as_ref(),
+            _ => None,
+        }
     }
 }
 
-#[async_trait::async_trait]
-trait ResponseExt {
-    async fn process_error(self) -> Result<Self>
-    where
-        Self: Sized;
-}
-
-#[async_trait::async_trait]
-impl ResponseExt for reqwest::Response {
-    async fn process_error(self) -> Result<Self>
-    where
-        Self: Sized,
-    {
-        let status = self.status();
-
-        if status.is_success() {
-            Ok(self)
-        } else {
-            // NOTE: it's a workaround thing but there are no alternative methods
-            // because API returns HTML instead of parseable JSON
-            // uhhhm also sorry for my broken english
-            if status == StatusCode::UNAUTHORIZED {
-                return Err(RevoltHttpError::Api(ApiError::Unauthenticated));
-            }
+/// Times a request rejected for exceeding the rate limit is sent again.
+const RATE_LIMIT_RETRIES: u32 = 5;
 
-            Err(RevoltHttpError::Api(ApiError::from(self.json().await?)))
-        }
-    }
-}
+/// Wait for a rejected request if the server didn't say how long.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
 
 #[derive(Debug)]
 pub struct RevoltHttp {
     base_url: String,
     client: Client,
+    transport: Arc<dyn Transport>,
     authentication: Authentication,
+    rate_limiter: Arc<RateLimiter>,
+    retry_policy: RetryPolicy,
 }
 
 impl RevoltHttp {
@@ -93,11 +81,131 @@ impl RevoltHttp {
         RevoltHttp::new_base_url(authentication, "https://api.revolt.chat")
     }
 
+    /// # Panics
+    ///
+    /// If the client can't be created, like [reqwest::Client::new].
     pub fn new_base_url(authentication: Authentication, base_url: impl Into<String>) -> Self {
+        RevoltHttp::builder(authentication)
+            .base_url(base_url)
+            .build()
+            .expect("failed to create HTTP client")
+    }
+
+    /// Configure the client, e.g. with timeouts or a proxy.
+    pub fn builder(authentication: Authentication) -> RevoltHttpBuilder {
+        RevoltHttpBuilder::new(authentication)
+    }
+
+    pub(crate) fn with_client(
+        authentication: Authentication,
+        base_url: String,
+        client: Client,
+        transport: Arc<dyn Transport>,
+        retry_policy: RetryPolicy,
+    ) -> Self {
         RevoltHttp {
-            base_url: base_url.into(),
-            client: Client::new(),
+            base_url,
+            client,
+            transport,
             authentication,
+            rate_limiter: Arc::default(),
+            retry_policy,
         }
     }
+
+    /// Current state of the rate limit buckets requests were made to.
+    pub fn rate_limits(&self) -> Vec<RateLimitBucket> {
+        self.rate_limiter.snapshot()
+    }
+
+    /// Start a request to a route of the API.
+    fn request(&self, route: Route) -> ApiRequest<'_> {
+        let mut builder = self
+            .client
+            .request(route.method(), format!("{}{}", self.base_url, route.path()));
+
+        if !matches!(self.authentication, Authentication::None) {
+            builder = builder.header(
+                self.authentication.header_key(),
+                self.authentication.value(),
+            );
+        }
+
+        ApiRequest::new(self, route, builder)
+    }
+
+    /// Send a request within the rate limits.
+    ///
+    /// Unsuccessful responses are returned as [RevoltHttpError::Api]. Waits if the bucket of the route is used up, and sends the request
+    /// again if the server rejected it for exceeding the limit anyway or
+    /// it failed in a way the [RetryPolicy] considers transient.
+    async fn execute(&self, route: &Route, request: RequestBuilder) -> Result<Response> {
+        let request = request.build()?;
+        let key = route.rate_limit_key();
+
+        let mut rate_limited = 0;
+        let mut attempt = 1;
+        loop {
+            self.rate_limiter.acquire(&key).await;
+
+            // Requests with streamed bodies can't be sent twice
+            let current = match request.try_clone() {
+                Some(current) => current,
+                None => {
+                    let response = self.transport.send(request).await?;
+                    self.rate_limiter.update(&key, response.headers());
+                    return check_status(route, response).await;
+                }
+            };
+
+            let response = match self.transport.send(current).await {
+                Ok(response) => response,
+                Err(err) => {
+                    let reached_server = !matches!(err, TransportError::Connect(_));
+                    if self.retry_policy.retries_error(&err)
+                        && self.retry_policy.allows(&request, attempt, reached_server)
+                    {
+                        sleep(self.retry_policy.delay(attempt)).await;
+                        attempt += 1;
+                        continue;
+                    }
+                    return Err(err.into());
+                }
+            };
+            self.rate_limiter.update(&key, response.headers());
+
+            if response.status() == StatusCode::TOO_MANY_REQUESTS
+                && rate_limited < RATE_LIMIT_RETRIES
+            {
+                let rejected = ApiErrorResponse::read(route.to_string(), response).await;
+                let retry_after = rejected.retry_after().unwrap_or(DEFAULT_RETRY_AFTER);
+                if !self.rate_limiter.exhaust(&key, retry_after) {
+                    // No bucket to queue up on, so wait here
+                    sleep(retry_after).await;
+                }
+                rate_limited += 1;
+                continue;
+            }
+
+            if self.retry_policy.retries_status(response.status())
+                && self.retry_policy.allows(&request, attempt, true)
+            {
+                sleep(self.retry_policy.delay(attempt)).await;
+                attempt += 1;
+                continue;
+            }
+
+            return check_status(route, response).await;
+        }
+    }
+}
+
+/// Turn unsuccessful responses into errors.
+async fn check_status(route: &Route, response: Response) -> Result<Response> {
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        let response = ApiErrorResponse::read(route.to_string(), response).await;
+        Err(RevoltHttpError::Api(Box::new(response)))
+    }
 }
@@ -0,0 +1,214 @@
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+use tokio::{
+    sync::Mutex as AsyncMutex,
+    time::{sleep_until, Instant},
+};
+
+const BUCKET_HEADER: &str = "x-ratelimit-bucket";
+const LIMIT_HEADER: &str = "x-ratelimit-limit";
+const REMAINING_HEADER: &str = "x-ratelimit-remaining";
+const RESET_AFTER_HEADER: &str = "x-ratelimit-reset-after";
+const RETRY_AFTER_HEADER: &str = "retry-after";
+
+/// Snapshot of a rate limit bucket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitBucket {
+    /// Bucket name given by the server
+    pub bucket: String,
+    /// Requests allowed per window
+    pub limit: u32,
+    /// Requests left in the current window
+    pub remaining: u32,
+    /// Time until the window resets, zero once it has
+    pub reset_after: Duration,
+}
+
+#[derive(Debug)]
+struct Window {
+    limit: u32,
+    remaining: u32,
+    reset_at: Instant,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Held while waiting for the window to reset, which queues up the requests behind it
+    queue: AsyncMutex<()>,
+    window: Mutex<Window>,
+}
+
+impl Bucket {
+    fn window(&self) -> MutexGuard<'_, Window> {
+        lock(&self.window)
+    }
+}
+
+/// Keeps requests within the rate limits the server reports.
+///
+/// The server assigns every route to a bucket and tells how many requests
+/// are left in it through the `X-RateLimit-*` response headers. Requests to
+/// a bucket that is used up wait for it to reset, in the order they were made.
+///
+/// Routes are tracked by their bucket in the route table together with the
+/// channel, server or user they act on, the same way the server keeps them
+/// apart. Requests to one channel never wait on the limits of another.
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiter {
+    /// Server bucket of every route key seen so far
+    routes: Mutex<HashMap<String, String>>,
+    buckets: Mutex<HashMap<String, Arc<Bucket>>>,
+}
+
+impl RateLimiter {
+    /// Wait until the bucket of a route allows another request.
+    ///
+    /// Routes are only limited once a response told their bucket.
+    pub(crate) async fn acquire(&self, route: &str) {
+        let bucket = match self.bucket_of(route) {
+            Some(bucket) => bucket,
+            None => return,
+        };
+
+        let _turn = bucket.queue.lock().await;
+        loop {
+            let reset_at = {
+                let mut window = bucket.window();
+                if Instant::now() >= window.reset_at {
+                    window.remaining = window.limit;
+                }
+
+                if window.remaining > 0 {
+                    window.remaining -= 1;
+                    return;
+                }
+
+                window.reset_at
+            };
+
+            sleep_until(reset_at).await;
+        }
+    }
+
+    /// Update a bucket from the headers of a response.
+    pub(crate) fn update(&self, route: &str, headers: &HeaderMap) {
+        let name = match header(headers, BUCKET_HEADER) {
+            Some(name) => name.to_string(),
+            None => return,
+        };
+        let limit = header(headers, LIMIT_HEADER).and_then(|v| v.parse().ok());
+        let remaining = header(headers, REMAINING_HEADER).and_then(|v| v.parse().ok());
+        let reset_after = header(headers, RESET_AFTER_HEADER).and_then(|v| v.parse().ok());
+
+        lock(&self.routes).insert(route.to_string(), name.clone());
+
+        let bucket = lock(&self.buckets)
+            .entry(name)
+            .or_insert_with(|| {
+                Arc::new(Bucket {
+                    queue: AsyncMutex::new(()),
+                    window: Mutex::new(Window {
+                        limit: 1,
+                        remaining: 1,
+                        reset_at: Instant::now(),
+                    }),
+                })
+            })
+            .clone();
+
+        let mut window = bucket.window();
+        if let Some(limit) = limit {
+            window.limit = limit;
+        }
+        if let Some(remaining) = remaining {
+            window.remaining = remaining;
+        }
+        if let Some(reset_after) = reset_after {
+            window.reset_at = Instant::now() + Duration::from_millis(reset_after);
+        }
+    }
+
+    /// Mark the bucket of a route as used up after the server rejected a request.
+    ///
+    /// Returns `false` if the bucket of the route is not known, the caller has to wait itself then.
+    pub(crate) fn exhaust(&self, route: &str, retry_after: Duration) -> bool {
+        match self.bucket_of(route) {
+            Some(bucket) => {
+                let mut window = bucket.window();
+                window.remaining = 0;
+                window.reset_at = window.reset_at.max(Instant::now() + retry_after);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Current state of all known buckets.
+    pub(crate) fn snapshot(&self) -> Vec<RateLimitBucket> {
+        let now = Instant::now();
+
+        lock(&self.buckets)
+            .iter()
+            .map(|(name, bucket)| {
+                let window = bucket.window();
+                let remaining = if now >= window.reset_at {
+                    window.limit
+                } else {
+                    window.remaining
+                };
+
+                RateLimitBucket {
+                    bucket: name.clone(),
+                    limit: window.limit,
+                    remaining,
+                    reset_after: window.reset_at.saturating_duration_since(now),
+                }
+            })
+            .collect()
+    }
+
+    fn bucket_of(&self, route: &str) -> Option<Arc<Bucket>> {
+        let name = lock(&self.routes).get(route)?.clone();
+        lock(&self.buckets).get(&name).cloned()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // Only counters and map entries change under these locks, they stay usable after a panic
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Body of a response rejected by the rate limit.
+#[derive(Deserialize)]
+struct RateLimited {
+    /// Milliseconds until the limit resets
+    retry_after: u64,
+}
+
+/// How long the server asked to wait before retrying a rejected request.
+///
+/// Looks at the `Retry-After` header in seconds, then at the reset of the
+/// bucket and finally at the `retry_after` field of the body in milliseconds.
+pub(crate) fn retry_after(headers: &HeaderMap, body: &str) -> Option<Duration> {
+    let number = |name| header(headers, name)?.parse::<u64>().ok();
+
+    if let Some(seconds) = number(RETRY_AFTER_HEADER) {
+        return Some(Duration::from_secs(seconds));
+    }
+    if let Some(millis) = number(RESET_AFTER_HEADER) {
+        return Some(Duration::from_millis(millis));
+    }
+
+    serde_json::from_str::<RateLimited>(body)
+        .ok()
+        .map(|body| Duration::from_millis(body.retry_after))
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name)?.to_str().ok()
+}
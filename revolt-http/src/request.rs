@@ -0,0 +1,58 @@
+use reqwest::{RequestBuilder, Response};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::{route::Route, Result, RevoltHttp};
+
+/// Request to a [Route] that is being put together.
+pub(crate) struct ApiRequest<'a> {
+    http: &'a RevoltHttp,
+    route: Route,
+    builder: RequestBuilder,
+}
+
+impl<'a> ApiRequest<'a> {
+    pub(crate) fn new(http: &'a RevoltHttp, route: Route, builder: RequestBuilder) -> Self {
+        ApiRequest {
+            http,
+            route,
+            builder,
+        }
+    }
+
+    /// Send a JSON body.
+    pub(crate) fn json(mut self, payload: &impl Serialize) -> Self {
+        self.builder = self.builder.json(payload);
+        self
+    }
+
+    /// Append query parameters.
+    pub(crate) fn query(mut self, query: &impl Serialize) -> Self {
+        self.builder = self.builder.query(query);
+        self
+    }
+
+    pub(crate) fn header(mut self, key: &str, value: &str) -> Self {
+        self.builder = self.builder.header(key, value);
+        self
+    }
+
+    /// Send the request and return the successful response as is.
+    pub(crate) async fn send(self) -> Result<Response> {
+        self.http.execute(&self.route, self.builder).await
+    }
+
+    /// Send the request and deserialize the response.
+    ///
+    /// Routes that return no body are deserialized from `null`, so `()` can be read from them.
+    pub(crate) async fn response<T: DeserializeOwned>(self) -> Result<T> {
+        let returns_body = self.route.returns_body();
+        let response = self.send().await?;
+
+        if returns_body {
+            Ok(response.json().await?)
+        } else {
+            Ok(serde_json::from_value(Value::Null)?)
+        }
+    }
+}
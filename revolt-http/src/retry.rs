@@ -0,0 +1,163 @@
+use crate::TransportError;
+use rand::Rng;
+use reqwest::{Method, Request, StatusCode};
+use std::time::Duration;
+
+/// Header that lets the server recognize a request it has already processed.
+pub(crate) const IDEMPOTENCY_KEY: &str = "idempotency-key";
+
+/// When requests that change something on every call may be sent again.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RetryNonIdempotent {
+    /// Never retry `POST` and `PATCH` requests
+    Never,
+    /// Retry them only if they carry an idempotency key,
+    /// e.g. messages sent with a nonce
+    #[default]
+    WithIdempotencyKey,
+    /// Always retry them, this may cause duplicate messages
+    Always,
+}
+
+/// Which failed requests are sent again and how long to wait in between.
+///
+/// Requests rejected by rate limits are retried regardless of this policy.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+    statuses: Vec<StatusCode>,
+    connect_errors: bool,
+    timeouts: bool,
+    request_errors: bool,
+    non_idempotent: RetryNonIdempotent,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(5),
+            statuses: vec![
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            connect_errors: true,
+            timeouts: true,
+            request_errors: true,
+            non_idempotent: RetryNonIdempotent::default(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Retry transient failures up to 3 attempts in total.
+    ///
+    /// Retries `502`, `503` and `504` responses, failed connections,
+    /// timeouts and connections dropped mid request.
+    pub fn new() -> Self {
+        RetryPolicy::default()
+    }
+
+    /// Never retry.
+    pub fn none() -> Self {
+        RetryPolicy::default().max_attempts(1)
+    }
+
+    /// Number of times a request is sent at most, including the first one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Bounds for the randomized exponential delay between attempts,
+    /// 250 milliseconds and 5 seconds by default.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_delay = initial;
+        self.max_delay = max;
+        self
+    }
+
+    /// Response statuses that are retried, replacing the default ones.
+    pub fn statuses(mut self, statuses: impl IntoIterator<Item = StatusCode>) -> Self {
+        self.statuses = statuses.into_iter().collect();
+        self
+    }
+
+    /// Whether to retry when no connection could be established.
+    pub fn retry_connect_errors(mut self, retry: bool) -> Self {
+        self.connect_errors = retry;
+        self
+    }
+
+    /// Whether to retry requests that timed out.
+    pub fn retry_timeouts(mut self, retry: bool) -> Self {
+        self.timeouts = retry;
+        self
+    }
+
+    /// Whether to retry requests that failed while being sent,
+    /// e.g. because the connection was reset.
+    pub fn retry_request_errors(mut self, retry: bool) -> Self {
+        self.request_errors = retry;
+        self
+    }
+
+    /// When `POST` and `PATCH` requests may be retried.
+    pub fn non_idempotent(mut self, non_idempotent: RetryNonIdempotent) -> Self {
+        self.non_idempotent = non_idempotent;
+        self
+    }
+
+    /// Whether a request may be sent again after its attempt-th try.
+    ///
+    /// Requests that never reached the server are safe to send again whatever their method.
+    pub(crate) fn allows(&self, request: &Request, attempt: u32, reached_server: bool) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+
+        let idempotent = *request.method() != Method::POST && *request.method() != Method::PATCH;
+        if idempotent || !reached_server {
+            return true;
+        }
+
+        match self.non_idempotent {
+            RetryNonIdempotent::Never => false,
+            RetryNonIdempotent::WithIdempotencyKey => {
+                request.headers().contains_key(IDEMPOTENCY_KEY)
+            }
+            RetryNonIdempotent::Always => true,
+        }
+    }
+
+    pub(crate) fn retries_status(&self, status: StatusCode) -> bool {
+        self.statuses.contains(&status)
+    }
+
+    pub(crate) fn retries_error(&self, err: &TransportError) -> bool {
+        match err {
+            TransportError::Connect(_) => self.connect_errors,
+            TransportError::Timeout(_) => self.timeouts,
+            TransportError::Request(_) => self.request_errors,
+            TransportError::Other(_) => false,
+        }
+    }
+
+    /// Delay before the next try after the attempt-th one.
+    ///
+    /// Full jitter: anything between zero and `initial_delay * 2^(attempt - 1)`,
+    /// but never more than `max_delay`.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let ceiling = self
+            .initial_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+
+        rand::thread_rng().gen_range(Duration::ZERO..=ceiling)
+    }
+}
@@ -4,14 +4,6 @@ use crate::prelude::*;
 
 impl RevoltHttp {
     pub async fn query_node(&self) -> Result<InstanceConfiguration> {
-        Ok(self
-            .client
-            .get(ep!(self, "/"))
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::QueryNode).response().await
     }
 }
@@ -0,0 +1,641 @@
+use reqwest::Method;
+use std::fmt;
+
+/// Every endpoint of the API the client calls.
+///
+/// Knows the method and path of a request, which rate limit bucket it
+/// counts towards and whether the response has a body worth reading.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Route {
+    // Core
+    QueryNode,
+
+    // Bots
+    CreateBot,
+    FetchPublicBot {
+        bot_id: String,
+    },
+    InviteBot {
+        bot_id: String,
+    },
+    FetchBot {
+        bot_id: String,
+    },
+    DeleteBot {
+        bot_id: String,
+    },
+    EditBot {
+        bot_id: String,
+    },
+    FetchOwnedBots,
+
+    // Channels
+    FetchChannel {
+        channel_id: String,
+    },
+    CloseChannel {
+        channel_id: String,
+    },
+    EditChannel {
+        channel_id: String,
+    },
+    CreateInvite {
+        channel_id: String,
+    },
+    SetRoleChannelPermissions {
+        channel_id: String,
+        role_id: String,
+    },
+    SetDefaultChannelPermissions {
+        channel_id: String,
+    },
+    JoinCall {
+        channel_id: String,
+    },
+
+    // Groups
+    FetchGroupMembers {
+        group_id: String,
+    },
+    CreateGroup,
+    AddMemberToGroup {
+        group_id: String,
+        member_id: String,
+    },
+    RemoveMemberFromGroup {
+        group_id: String,
+        member_id: String,
+    },
+
+    // Messaging
+    AcknowledgeMessage {
+        channel_id: String,
+        message_id: String,
+    },
+    FetchMessages {
+        channel_id: String,
+    },
+    SendMessage {
+        channel_id: String,
+    },
+    SearchForMessages {
+        channel_id: String,
+    },
+    FetchMessage {
+        channel_id: String,
+        message_id: String,
+    },
+    DeleteMessage {
+        channel_id: String,
+        message_id: String,
+    },
+    EditMessage {
+        channel_id: String,
+        message_id: String,
+    },
+    BulkDeleteMessages {
+        channel_id: String,
+    },
+
+    // Interactions
+    AddReactionToMessage {
+        channel_id: String,
+        message_id: String,
+        emoji: String,
+    },
+    RemoveReactionToMessage {
+        channel_id: String,
+        message_id: String,
+        emoji: String,
+    },
+    RemoveAllReactionsFromMessage {
+        channel_id: String,
+        message_id: String,
+    },
+
+    // Servers
+    CreateServer,
+    FetchServer {
+        server_id: String,
+    },
+    DeleteOrLeaveServer {
+        server_id: String,
+    },
+    EditServer {
+        server_id: String,
+    },
+    MarkServerAsRead {
+        server_id: String,
+    },
+    CreateChannel {
+        server_id: String,
+    },
+
+    // Server members
+    FetchMember {
+        server_id: String,
+        member_id: String,
+    },
+    FetchMembers {
+        server_id: String,
+    },
+    EditMember {
+        server_id: String,
+        member_id: String,
+    },
+    KickMember {
+        server_id: String,
+        member_id: String,
+    },
+
+    // Server bans
+    BanUser {
+        server_id: String,
+        user_id: String,
+    },
+    UnbanUser {
+        server_id: String,
+        user_id: String,
+    },
+    FetchBans {
+        server_id: String,
+    },
+
+    // Server permissions
+    CreateRole {
+        server_id: String,
+    },
+    EditRole {
+        server_id: String,
+        role_id: String,
+    },
+    DeleteRole {
+        server_id: String,
+        role_id: String,
+    },
+    SetRoleServerPermissions {
+        server_id: String,
+        role_id: String,
+    },
+    SetDefaultServerPermissions {
+        server_id: String,
+    },
+
+    // Emojis
+    FetchEmoji {
+        emoji_id: String,
+    },
+    CreateEmoji {
+        emoji_id: String,
+    },
+    DeleteEmoji {
+        emoji_id: String,
+    },
+    FetchServerEmojis {
+        server_id: String,
+    },
+
+    // Users
+    FetchSelf,
+    EditUser,
+    FetchUser {
+        user_id: String,
+    },
+    ChangeUsername,
+    FetchDefaultAvatar {
+        user_id: String,
+    },
+    FetchUserProfile {
+        user_id: String,
+    },
+    FetchDirectMessageChannels,
+    OpenDirectMessage {
+        user_id: String,
+    },
+    FetchMutualFriendsAndServers {
+        user_id: String,
+    },
+    AcceptFriendRequest {
+        user_id: String,
+    },
+    RemoveOrDenyFriend {
+        user_id: String,
+    },
+    BlockUser {
+        user_id: String,
+    },
+    UnblockUser {
+        user_id: String,
+    },
+    SendFriendRequest,
+}
+
+impl Route {
+    pub(crate) fn method(&self) -> Method {
+        use Route::*;
+
+        match self {
+            QueryNode
+            | FetchPublicBot { .. }
+            | FetchBot { .. }
+            | FetchOwnedBots
+            | FetchChannel { .. }
+            | FetchGroupMembers { .. }
+            | FetchMessages { .. }
+            | FetchMessage { .. }
+            | FetchServer { .. }
+            | FetchMember { .. }
+            | FetchMembers { .. }
+            | FetchBans { .. }
+            | FetchEmoji { .. }
+            | FetchServerEmojis { .. }
+            | FetchSelf
+            | FetchUser { .. }
+            | FetchDefaultAvatar { .. }
+            | FetchUserProfile { .. }
+            | FetchDirectMessageChannels
+            | OpenDirectMessage { .. }
+            | FetchMutualFriendsAndServers { .. } => Method::GET,
+
+            CreateBot
+            | InviteBot { .. }
+            | CreateInvite { .. }
+            | JoinCall { .. }
+            | CreateGroup
+            | SendMessage { .. }
+            | SearchForMessages { .. }
+            | CreateServer
+            | CreateChannel { .. }
+            | CreateRole { .. }
+            | SendFriendRequest => Method::POST,
+
+            SetRoleChannelPermissions { .. }
+            | SetDefaultChannelPermissions { .. }
+            | AddMemberToGroup { .. }
+            | AcknowledgeMessage { .. }
+            | AddReactionToMessage { .. }
+            | MarkServerAsRead { .. }
+            | BanUser { .. }
+            | SetRoleServerPermissions { .. }
+            | SetDefaultServerPermissions { .. }
+            | CreateEmoji { .. }
+            | AcceptFriendRequest { .. }
+            | BlockUser { .. } => Method::PUT,
+
+            EditBot { .. }
+            | EditChannel { .. }
+            | EditMessage { .. }
+            | EditServer { .. }
+            | EditMember { .. }
+            | EditRole { .. }
+            | EditUser
+            | ChangeUsername => Method::PATCH,
+
+            DeleteBot { .. }
+            | CloseChannel { .. }
+            | RemoveMemberFromGroup { .. }
+            | DeleteMessage { .. }
+            | BulkDeleteMessages { .. }
+            | RemoveReactionToMessage { .. }
+            | RemoveAllReactionsFromMessage { .. }
+            | DeleteOrLeaveServer { .. }
+            | KickMember { .. }
+            | UnbanUser { .. }
+            | DeleteRole { .. }
+            | DeleteEmoji { .. }
+            | RemoveOrDenyFriend { .. }
+            | UnblockUser { .. } => Method::DELETE,
+        }
+    }
+
+    /// Path relative to the API root, without query.
+    pub(crate) fn path(&self) -> String {
+        use Route::*;
+
+        match self {
+            QueryNode => "/".to_string(),
+
+            CreateBot => "/bots/create".to_string(),
+            FetchPublicBot { bot_id } | InviteBot { bot_id } => format!("/bots/{bot_id}/invite"),
+            FetchBot { bot_id } | DeleteBot { bot_id } | EditBot { bot_id } => {
+                format!("/bots/{bot_id}")
+            }
+            FetchOwnedBots => "/bots/@me".to_string(),
+
+            FetchChannel { channel_id }
+            | CloseChannel { channel_id }
+            | EditChannel { channel_id } => format!("/channels/{channel_id}"),
+            CreateInvite { channel_id } => format!("/channels/{channel_id}/invites"),
+            SetRoleChannelPermissions {
+                channel_id,
+                role_id,
+            } => format!("/channels/{channel_id}/permissions/{role_id}"),
+            SetDefaultChannelPermissions { channel_id } => {
+                format!("/channels/{channel_id}/permissions/default")
+            }
+            JoinCall { channel_id } => format!("/channels/{channel_id}/join_call"),
+
+            FetchGroupMembers { group_id } => format!("/channels/{group_id}/members"),
+            CreateGroup => "/channels/create".to_string(),
+            AddMemberToGroup {
+                group_id,
+                member_id,
+            }
+            | RemoveMemberFromGroup {
+                group_id,
+                member_id,
+            } => format!("/channels/{group_id}/recipients/{member_id}"),
+
+            AcknowledgeMessage {
+                channel_id,
+                message_id,
+            } => format!("/channels/{channel_id}/ack/{message_id}"),
+            FetchMessages { channel_id } | SendMessage { channel_id } => {
+                format!("/channels/{channel_id}/messages")
+            }
+            SearchForMessages { channel_id } => format!("/channels/{channel_id}/messages/search"),
+            FetchMessage {
+                channel_id,
+                message_id,
+            }
+            | DeleteMessage {
+                channel_id,
+                message_id,
+            }
+            | EditMessage {
+                channel_id,
+                message_id,
+            } => format!("/channels/{channel_id}/messages/{message_id}"),
+            BulkDeleteMessages { channel_id } => format!("/channels/{channel_id}/messages/bulk"),
+
+            AddReactionToMessage {
+                channel_id,
+                message_id,
+                emoji,
+            }
+            | RemoveReactionToMessage {
+                channel_id,
+                message_id,
+                emoji,
+            } => format!("/channels/{channel_id}/messages/{message_id}/reactions/{emoji}"),
+            RemoveAllReactionsFromMessage {
+                channel_id,
+                message_id,
+            } => format!("/channels/{channel_id}/messages/{message_id}/reactions"),
+
+            CreateServer => "/servers/create".to_string(),
+            FetchServer { server_id }
+            | DeleteOrLeaveServer { server_id }
+            | EditServer { server_id } => format!("/servers/{server_id}"),
+            MarkServerAsRead { server_id } => format!("/servers/{server_id}/ack"),
+            CreateChannel { server_id } => format!("/servers/{server_id}/channels"),
+
+            FetchMember {
+                server_id,
+                member_id,
+            }
+            | EditMember {
+                server_id,
+                member_id,
+            }
+            | KickMember {
+                server_id,
+                member_id,
+            } => format!("/servers/{server_id}/members/{member_id}"),
+            FetchMembers { server_id } => format!("/servers/{server_id}/members"),
+
+            BanUser { server_id, user_id } | UnbanUser { server_id, user_id } => {
+                format!("/servers/{server_id}/bans/{user_id}")
+            }
+            FetchBans { server_id } => format!("/servers/{server_id}/bans"),
+
+            CreateRole { server_id } => format!("/servers/{server_id}/roles"),
+            EditRole { server_id, role_id } | DeleteRole { server_id, role_id } => {
+                format!("/servers/{server_id}/roles/{role_id}")
+            }
+            SetRoleServerPermissions { server_id, role_id } => {
+                format!("/servers/{server_id}/permissions/{role_id}")
+            }
+            SetDefaultServerPermissions { server_id } => {
+                format!("/servers/{server_id}/permissions/default")
+            }
+
+            FetchEmoji { emoji_id } | CreateEmoji { emoji_id } | DeleteEmoji { emoji_id } => {
+                format!("/custom/emoji/{emoji_id}")
+            }
+            FetchServerEmojis { server_id } => format!("/servers/{server_id}/emojis"),
+
+            FetchSelf | EditUser => "/users/@me".to_string(),
+            FetchUser { user_id } => format!("/users/{user_id}"),
+            ChangeUsername => "/users/@me/username".to_string(),
+            FetchDefaultAvatar { user_id } => format!("/users/{user_id}/default_avatar"),
+            FetchUserProfile { user_id } => format!("/users/{user_id}/profile"),
+            FetchDirectMessageChannels => "/users/dms".to_string(),
+            OpenDirectMessage { user_id } => format!("/users/{user_id}/dm"),
+            FetchMutualFriendsAndServers { user_id } => format!("/users/{user_id}/mutual"),
+            AcceptFriendRequest { user_id } | RemoveOrDenyFriend { user_id } => {
+                format!("/users/{user_id}/friend")
+            }
+            BlockUser { user_id } | UnblockUser { user_id } => format!("/users/{user_id}/block"),
+            SendFriendRequest => "/users/friend".to_string(),
+        }
+    }
+
+    /// Rate limit bucket the server counts the route towards.
+    pub(crate) fn bucket(&self) -> &'static str {
+        use Route::*;
+
+        match self {
+            QueryNode | FetchEmoji { .. } | CreateEmoji { .. } | DeleteEmoji { .. } => "default",
+
+            CreateBot
+            | FetchPublicBot { .. }
+            | InviteBot { .. }
+            | FetchBot { .. }
+            | DeleteBot { .. }
+            | EditBot { .. }
+            | FetchOwnedBots => "bots",
+
+            SendMessage { .. } => "messaging",
+
+            FetchChannel { .. }
+            | CloseChannel { .. }
+            | EditChannel { .. }
+            | CreateInvite { .. }
+            | SetRoleChannelPermissions { .. }
+            | SetDefaultChannelPermissions { .. }
+            | JoinCall { .. }
+            | FetchGroupMembers { .. }
+            | CreateGroup
+            | AddMemberToGroup { .. }
+            | RemoveMemberFromGroup { .. }
+            | AcknowledgeMessage { .. }
+            | FetchMessages { .. }
+            | SearchForMessages { .. }
+            | FetchMessage { .. }
+            | DeleteMessage { .. }
+            | EditMessage { .. }
+            | BulkDeleteMessages { .. }
+            | AddReactionToMessage { .. }
+            | RemoveReactionToMessage { .. }
+            | RemoveAllReactionsFromMessage { .. } => "channels",
+
+            CreateServer
+            | FetchServer { .. }
+            | DeleteOrLeaveServer { .. }
+            | EditServer { .. }
+            | MarkServerAsRead { .. }
+            | CreateChannel { .. }
+            | FetchMember { .. }
+            | FetchMembers { .. }
+            | EditMember { .. }
+            | KickMember { .. }
+            | BanUser { .. }
+            | UnbanUser { .. }
+            | FetchBans { .. }
+            | CreateRole { .. }
+            | EditRole { .. }
+            | DeleteRole { .. }
+            | SetRoleServerPermissions { .. }
+            | SetDefaultServerPermissions { .. }
+            | FetchServerEmojis { .. } => "servers",
+
+            FetchSelf
+            | EditUser
+            | FetchUser { .. }
+            | ChangeUsername
+            | FetchDefaultAvatar { .. }
+            | FetchUserProfile { .. }
+            | FetchDirectMessageChannels
+            | OpenDirectMessage { .. }
+            | FetchMutualFriendsAndServers { .. }
+            | AcceptFriendRequest { .. }
+            | RemoveOrDenyFriend { .. }
+            | BlockUser { .. }
+            | UnblockUser { .. }
+            | SendFriendRequest => "users",
+        }
+    }
+
+    /// Id of the resource the server keeps separate limits for within a bucket.
+    fn resource(&self) -> Option<&str> {
+        use Route::*;
+
+        match self {
+            QueryNode
+            | CreateBot
+            | FetchOwnedBots
+            | CreateGroup
+            | CreateServer
+            | FetchSelf
+            | EditUser
+            | ChangeUsername
+            | FetchDirectMessageChannels
+            | SendFriendRequest => None,
+
+            FetchPublicBot { bot_id }
+            | InviteBot { bot_id }
+            | FetchBot { bot_id }
+            | DeleteBot { bot_id }
+            | EditBot { bot_id } => Some(bot_id),
+
+            FetchChannel { channel_id }
+            | CloseChannel { channel_id }
+            | EditChannel { channel_id }
+            | CreateInvite { channel_id }
+            | SetRoleChannelPermissions { channel_id, .. }
+            | SetDefaultChannelPermissions { channel_id }
+            | JoinCall { channel_id }
+            | AcknowledgeMessage { channel_id, .. }
+            | FetchMessages { channel_id }
+            | SendMessage { channel_id }
+            | SearchForMessages { channel_id }
+            | FetchMessage { channel_id, .. }
+            | DeleteMessage { channel_id, .. }
+            | EditMessage { channel_id, .. }
+            | BulkDeleteMessages { channel_id }
+            | AddReactionToMessage { channel_id, .. }
+            | RemoveReactionToMessage { channel_id, .. }
+            | RemoveAllReactionsFromMessage { channel_id, .. } => Some(channel_id),
+
+            FetchGroupMembers { group_id }
+            | AddMemberToGroup { group_id, .. }
+            | RemoveMemberFromGroup { group_id, .. } => Some(group_id),
+
+            FetchServer { server_id }
+            | DeleteOrLeaveServer { server_id }
+            | EditServer { server_id }
+            | MarkServerAsRead { server_id }
+            | CreateChannel { server_id }
+            | FetchMember { server_id, .. }
+            | FetchMembers { server_id }
+            | EditMember { server_id, .. }
+            | KickMember { server_id, .. }
+            | BanUser { server_id, .. }
+            | UnbanUser { server_id, .. }
+            | FetchBans { server_id }
+            | CreateRole { server_id }
+            | EditRole { server_id, .. }
+            | DeleteRole { server_id, .. }
+            | SetRoleServerPermissions { server_id, .. }
+            | SetDefaultServerPermissions { server_id }
+            | FetchServerEmojis { server_id } => Some(server_id),
+
+            FetchEmoji { emoji_id } | CreateEmoji { emoji_id } | DeleteEmoji { emoji_id } => {
+                Some(emoji_id)
+            }
+
+            FetchUser { user_id }
+            | FetchDefaultAvatar { user_id }
+            | FetchUserProfile { user_id }
+            | OpenDirectMessage { user_id }
+            | FetchMutualFriendsAndServers { user_id }
+            | AcceptFriendRequest { user_id }
+            | RemoveOrDenyFriend { user_id }
+            | BlockUser { user_id }
+            | UnblockUser { user_id } => Some(user_id),
+        }
+    }
+
+    /// Key the rate limiter tracks the route by, its bucket and main resource id,
+    /// e.g. `messaging/01G...` for messages sent to a channel.
+    pub(crate) fn rate_limit_key(&self) -> String {
+        match self.resource() {
+            Some(resource) => format!("{}/{}", self.bucket(), resource),
+            None => self.bucket().to_string(),
+        }
+    }
+
+    /// Whether the response carries a body, otherwise it is empty on success.
+    pub(crate) fn returns_body(&self) -> bool {
+        use Route::*;
+
+        !matches!(
+            self,
+            InviteBot { .. }
+                | DeleteBot { .. }
+                | CloseChannel { .. }
+                | AddMemberToGroup { .. }
+                | RemoveMemberFromGroup { .. }
+                | AcknowledgeMessage { .. }
+                | DeleteMessage { .. }
+                | BulkDeleteMessages { .. }
+                | AddReactionToMessage { .. }
+                | RemoveReactionToMessage { .. }
+                | RemoveAllReactionsFromMessage { .. }
+                | DeleteOrLeaveServer { .. }
+                | MarkServerAsRead { .. }
+                | KickMember { .. }
+                | UnbanUser { .. }
+                | DeleteRole { .. }
+                | DeleteEmoji { .. }
+        )
+    }
+}
+
+/// Method and path, e.g. `GET /users/@me`.
+impl fmt::Display for Route {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method(), self.path())
+    }
+}
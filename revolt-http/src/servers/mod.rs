@@ -1 +1,4 @@
+mod server_bans;
 mod server_information;
+mod server_members;
+mod server_permissions;
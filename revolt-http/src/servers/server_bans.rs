@@ -0,0 +1,52 @@
+use crate::prelude::*;
+use revolt_models::{
+    payload::BanUserPayload,
+    server::{BulkBanResponse, ServerBan},
+};
+
+impl RevoltHttp {
+    /// Ban a user from the server by their id.
+    ///
+    /// Requires [Permission::BanMembers].
+    pub async fn ban_user(
+        &self,
+        server_id: impl Into<String>,
+        user_id: impl Into<String>,
+        payload: BanUserPayload,
+    ) -> Result<ServerBan> {
+        self.request(Route::BanUser {
+            server_id: server_id.into(),
+            user_id: user_id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
+    }
+
+    /// Remove a user's ban.
+    ///
+    /// Requires [Permission::BanMembers].
+    pub async fn unban_user(
+        &self,
+        server_id: impl Into<String>,
+        user_id: impl Into<String>,
+    ) -> Result<()> {
+        self.request(Route::UnbanUser {
+            server_id: server_id.into(),
+            user_id: user_id.into(),
+        })
+        .response()
+        .await
+    }
+
+    /// Fetch all bans on a server along with the banned users.
+    ///
+    /// Requires [Permission::BanMembers].
+    pub async fn fetch_bans(&self, server_id: impl Into<String>) -> Result<BulkBanResponse> {
+        self.request(Route::FetchBans {
+            server_id: server_id.into(),
+        })
+        .response()
+        .await
+    }
+}
@@ -8,45 +8,28 @@ use revolt_models::{
 impl RevoltHttp {
     /// Create a new server.
     pub async fn create_server(&self, payload: CreateServerPayload) -> Result<Server> {
-        Ok(self
-            .client
-            .post(ep!(self, "/servers/create"))
+        self.request(Route::CreateServer)
             .json(&payload)
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+            .response()
+            .await
     }
 
-    /// Deletes a server if owner otherwise leaves.
+    /// Fetch a server by its id.
     pub async fn fetch_server(&self, id: impl Into<String>) -> Result<Server> {
-        Ok(self
-            .client
-            .get(ep!(self, "/servers/{}", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::FetchServer {
+            server_id: id.into(),
+        })
+        .response()
+        .await
     }
 
     /// Deletes a server if owner otherwise leaves.
     pub async fn delete_or_leave_server(&self, id: impl Into<String>) -> Result<()> {
-        self.client
-            .delete(ep!(self, "/servers/{}", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?;
-        Ok(())
+        self.request(Route::DeleteOrLeaveServer {
+            server_id: id.into(),
+        })
+        .response()
+        .await
     }
 
     /// Edit a server by its id.
@@ -55,31 +38,21 @@ impl RevoltHttp {
         id: impl Into<String>,
         payload: EditServerPayload,
     ) -> Result<Server> {
-        Ok(self
-            .client
-            .patch(ep!(self, "/servers/{}", id.into()))
-            .json(&payload)
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::EditServer {
+            server_id: id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
     }
 
     /// Mark all channels in a server as read.
     pub async fn mark_server_as_read(&self, id: impl Into<String>) -> Result<()> {
-        self.client
-            .put(ep!(self, "/servers/{}/ack", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?;
-        Ok(())
+        self.request(Route::MarkServerAsRead {
+            server_id: id.into(),
+        })
+        .response()
+        .await
     }
 
     /// Create a new Text or Voice channel
@@ -88,16 +61,11 @@ impl RevoltHttp {
         server_id: impl Into<String>,
         payload: CreateChannelPayload,
     ) -> Result<Channel> {
-        Ok(self
-            .client
-            .post(ep!(self, "/servers/{}/channels", server_id.into()))
-            .json(&payload)
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::CreateChannel {
+            server_id: server_id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
     }
 }
@@ -0,0 +1,71 @@
+use crate::prelude::*;
+use revolt_models::{
+    member::{BulkMemberResponse, Member},
+    payload::{EditMemberPayload, FetchMembersPayload},
+};
+
+impl RevoltHttp {
+    /// Retrieve a member of a server.
+    pub async fn fetch_member(
+        &self,
+        server_id: impl Into<String>,
+        member_id: impl Into<String>,
+    ) -> Result<Member> {
+        self.request(Route::FetchMember {
+            server_id: server_id.into(),
+            member_id: member_id.into(),
+        })
+        .response()
+        .await
+    }
+
+    /// Fetch all server members along with their user objects.
+    pub async fn fetch_members(
+        &self,
+        server_id: impl Into<String>,
+        payload: FetchMembersPayload,
+    ) -> Result<BulkMemberResponse> {
+        self.request(Route::FetchMembers {
+            server_id: server_id.into(),
+        })
+        .query(&payload)
+        .response()
+        .await
+    }
+
+    /// Edit a member's nickname, avatar, roles or timeout.
+    ///
+    /// Requires [Permission::ManageNicknames], [Permission::RemoveAvatars],
+    /// [Permission::AssignRoles] or [Permission::TimeoutMembers] respectively
+    /// when editing others.
+    pub async fn edit_member(
+        &self,
+        server_id: impl Into<String>,
+        member_id: impl Into<String>,
+        payload: EditMemberPayload,
+    ) -> Result<Member> {
+        self.request(Route::EditMember {
+            server_id: server_id.into(),
+            member_id: member_id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
+    }
+
+    /// Removes a member from the server.
+    ///
+    /// Requires [Permission::KickMembers].
+    pub async fn kick_member(
+        &self,
+        server_id: impl Into<String>,
+        member_id: impl Into<String>,
+    ) -> Result<()> {
+        self.request(Route::KickMember {
+            server_id: server_id.into(),
+            member_id: member_id.into(),
+        })
+        .response()
+        .await
+    }
+}
@@ -0,0 +1,94 @@
+use crate::prelude::*;
+use revolt_models::{
+    payload::{
+        CreateRolePayload, EditRolePayload, SetDefaultServerPermissionPayload,
+        SetRolePermissionPayload,
+    },
+    server::{NewRoleResponse, Role, Server},
+};
+
+impl RevoltHttp {
+    /// Creates a new server role.
+    ///
+    /// Requires [Permission::ManageRole].
+    pub async fn create_role(
+        &self,
+        server_id: impl Into<String>,
+        payload: CreateRolePayload,
+    ) -> Result<NewRoleResponse> {
+        self.request(Route::CreateRole {
+            server_id: server_id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
+    }
+
+    /// Edit a role by its id.
+    ///
+    /// Requires [Permission::ManageRole].
+    pub async fn edit_role(
+        &self,
+        server_id: impl Into<String>,
+        role_id: impl Into<String>,
+        payload: EditRolePayload,
+    ) -> Result<Role> {
+        self.request(Route::EditRole {
+            server_id: server_id.into(),
+            role_id: role_id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
+    }
+
+    /// Delete a server role by its id.
+    ///
+    /// Requires [Permission::ManageRole].
+    pub async fn delete_role(
+        &self,
+        server_id: impl Into<String>,
+        role_id: impl Into<String>,
+    ) -> Result<()> {
+        self.request(Route::DeleteRole {
+            server_id: server_id.into(),
+            role_id: role_id.into(),
+        })
+        .response()
+        .await
+    }
+
+    /// Sets permissions for the specified role in the server.
+    ///
+    /// Requires [Permission::ManagePermissions].
+    pub async fn set_role_server_permissions(
+        &self,
+        server_id: impl Into<String>,
+        role_id: impl Into<String>,
+        payload: SetRolePermissionPayload,
+    ) -> Result<Server> {
+        self.request(Route::SetRoleServerPermissions {
+            server_id: server_id.into(),
+            role_id: role_id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
+    }
+
+    /// Sets permissions for the default role in the server.
+    ///
+    /// Requires [Permission::ManagePermissions].
+    pub async fn set_default_server_permissions(
+        &self,
+        server_id: impl Into<String>,
+        payload: SetDefaultServerPermissionPayload,
+    ) -> Result<Server> {
+        self.request(Route::SetDefaultServerPermissions {
+            server_id: server_id.into(),
+        })
+        .json(&payload)
+        .response()
+        .await
+    }
+}
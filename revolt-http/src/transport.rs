@@ -0,0 +1,68 @@
+use reqwest::{Client, Request, Response};
+use std::{error::Error, fmt::Debug, sync::Arc};
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// Sends requests over the network, or wherever else they should go.
+///
+/// [reqwest::Client] is the default, implement this to use another HTTP
+/// stack or to answer requests in tests without a server.
+///
+/// Responses can be built from [http::Response](https://docs.rs/http/0.2/http/response/struct.Response.html)
+/// with [Response::from]. Unsuccessful statuses are returned as responses
+/// too, errors are meant for requests that got no response at all.
+#[async_trait::async_trait]
+pub trait Transport: Debug + Send + Sync {
+    async fn send(&self, request: Request) -> Result<Response, TransportError>;
+}
+
+#[async_trait::async_trait]
+impl Transport for Client {
+    async fn send(&self, request: Request) -> Result<Response, TransportError> {
+        Ok(self.execute(request).await?)
+    }
+}
+
+/// Lets the caller keep a handle on the transport, e.g. to look at what a fake recorded.
+#[async_trait::async_trait]
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    async fn send(&self, request: Request) -> Result<Response, TransportError> {
+        (**self).send(request).await
+    }
+}
+
+/// Request that got no response.
+///
+/// The kind decides whether the [RetryPolicy](crate::RetryPolicy) sends it again.
+#[derive(Debug, thiserror::Error)]
+pub enum TransportError {
+    /// No connection could be established, so the server never saw the request
+    #[error("Could not connect: {0}")]
+    Connect(BoxError),
+
+    /// No response arrived in time
+    #[error("Request timed out: {0}")]
+    Timeout(BoxError),
+
+    /// Failed while being sent, e.g. because the connection was reset
+    #[error("Request failed: {0}")]
+    Request(BoxError),
+
+    /// Anything else, never retried
+    #[error("{0}")]
+    Other(BoxError),
+}
+
+impl From<reqwest::Error> for TransportError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_connect() {
+            TransportError::Connect(err.into())
+        } else if err.is_timeout() {
+            TransportError::Timeout(err.into())
+        } else if err.is_request() {
+            TransportError::Request(err.into())
+        } else {
+            TransportError::Other(err.into())
+        }
+    }
+}
@@ -7,86 +7,44 @@ use revolt_models::{
 impl RevoltHttp {
     /// This fetches your direct messages, including any DM and group DM conversations.
     pub async fn fetch_mutual_friends_and_servers(&self, id: impl Into<String>) -> Result<Mutuals> {
-        Ok(self
-            .client
-            .get(ep!(self, "/users/{}/mutual", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::FetchMutualFriendsAndServers { user_id: id.into() })
+            .response()
+            .await
     }
 
     /// Accept another user's friend request
     pub async fn accept_friend_request(&self, id: impl Into<String>) -> Result<User> {
-        Ok(self
-            .client
-            .put(ep!(self, "/users/{}/friend", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::AcceptFriendRequest { user_id: id.into() })
+            .response()
+            .await
     }
 
     /// Denies another user's friend request or removes an existing friend.
     pub async fn remove_or_deny_friend(&self, id: impl Into<String>) -> Result<User> {
-        Ok(self
-            .client
-            .delete(ep!(self, "/users/{}/friend", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::RemoveOrDenyFriend { user_id: id.into() })
+            .response()
+            .await
     }
 
     /// Block another user by their id.
     pub async fn block_user(&self, id: impl Into<String>) -> Result<User> {
-        Ok(self
-            .client
-            .put(ep!(self, "/users/{}/block", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::BlockUser { user_id: id.into() })
+            .response()
+            .await
     }
 
     /// Unblock another user by their id.
     pub async fn unblock_user(&self, id: impl Into<String>) -> Result<User> {
-        Ok(self
-            .client
-            .delete(ep!(self, "/users/{}/block", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::UnblockUser { user_id: id.into() })
+            .response()
+            .await
     }
 
     /// Send a friend request to another user.
     pub async fn send_friend_request(&self, payload: SendFriendRequestPayload) -> Result<User> {
-        Ok(self
-            .client
-            .post(ep!(self, "/users/friend"))
-            .auth(&self.authentication)
+        self.request(Route::SendFriendRequest)
             .json(&payload)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+            .response()
+            .await
     }
 }
@@ -7,72 +7,38 @@ use revolt_models::{
 impl RevoltHttp {
     /// Retrieve your user information.
     pub async fn fetch_self(&self) -> Result<User> {
-        Ok(self
-            .client
-            .get(ep!(self, "/users/@me"))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::FetchSelf).response().await
     }
 
     /// Edit currently authenticated user.
     pub async fn edit_user(&self, payload: EditUserPayload) -> Result<User> {
-        Ok(self
-            .client
-            .patch(ep!(self, "/users/@me"))
-            .auth(&self.authentication)
+        self.request(Route::EditUser)
             .json(&payload)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+            .response()
+            .await
     }
 
     /// Fetch a user's information.
     pub async fn fetch_user(&self, id: impl Into<String>) -> Result<User> {
-        Ok(self
-            .client
-            .get(ep!(self, "/users/{}", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::FetchUser { user_id: id.into() })
+            .response()
+            .await
     }
 
     /// Change your username.
     pub async fn change_username(&self, payload: ChangeUsernamePayload) -> Result<User> {
-        Ok(self
-            .client
-            .patch(ep!(self, "/users/@me/username"))
-            .auth(&self.authentication)
+        self.request(Route::ChangeUsername)
             .json(&payload)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+            .response()
+            .await
     }
 
     /// This returns a default avatar based on the given id.
     pub async fn fetch_default_avatar(&self, id: impl Into<String>) -> Result<Vec<u8>> {
         Ok(self
-            .client
-            .get(ep!(self, "/users/{}/default_avatar", id.into()))
-            .auth(&self.authentication)
+            .request(Route::FetchDefaultAvatar { user_id: id.into() })
             .send()
             .await?
-            .process_error()
-            .await?
             .bytes()
             .await?
             .into())
@@ -82,15 +48,8 @@ impl RevoltHttp {
     ///
     ///Will fail if you do not have permission to access the other user's profile.
     pub async fn fetch_user_profile(&self, id: impl Into<String>) -> Result<UserProfile> {
-        Ok(self
-            .client
-            .get(ep!(self, "/users/{}/profile", id.into()))
-            .auth(&self.authentication)
-            .send()
-            .await?
-            .process_error()
-            .await?
-            .json()
-            .await?)
+        self.request(Route::FetchUserProfile { user_id: id.into() })
+            .response()
+            .await
     }
 }
@@ -0,0 +1,59 @@
+mod common;
+
+use common::mock::Mock;
+use reqwest::header::{HeaderMap, HeaderValue};
+use revolt_http::{RetryPolicy, RevoltHttp, RevoltHttpError, TransportError};
+use revolt_models::authentication::Authentication;
+use serde_json::Value;
+use std::time::Duration;
+
+#[tokio::test]
+async fn user_agent_and_default_headers() {
+    let mut mock = Mock::start().await;
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-deployment", HeaderValue::from_static("test"));
+    let http = RevoltHttp::builder(Authentication::BotToken("token".to_string()))
+        .base_url(mock.url.clone())
+        .user_agent("custom-agent/1.0")
+        .default_headers(headers)
+        .build()
+        .unwrap();
+
+    http.delete_bot("B").await.unwrap();
+
+    let received = mock.expect("DELETE", "/bots/B", Value::Null).await;
+    assert_eq!(received.headers["user-agent"], "custom-agent/1.0");
+    assert_eq!(received.headers["x-deployment"], "test");
+}
+
+#[tokio::test]
+async fn request_timeout() {
+    let mock = Mock::silent().await;
+
+    let http = RevoltHttp::builder(Authentication::BotToken("token".to_string()))
+        .base_url(mock.url.clone())
+        .timeout(Duration::from_millis(100))
+        .retry_policy(RetryPolicy::none())
+        .build()
+        .unwrap();
+
+    let err = http.delete_bot("B").await.unwrap_err();
+
+    assert!(matches!(
+        err,
+        RevoltHttpError::Transport(TransportError::Timeout(_))
+    ));
+}
+
+#[test]
+fn client_options_next_to_client_are_rejected() {
+    let builder = RevoltHttp::builder(Authentication::BotToken("token".to_string()))
+        .client(reqwest::Client::new());
+
+    assert!(builder.clone().build().is_ok());
+    assert!(matches!(
+        builder.timeout(Duration::from_secs(1)).build(),
+        Err(RevoltHttpError::ClientOptionsIgnored)
+    ));
+}
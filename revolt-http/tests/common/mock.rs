@@ -0,0 +1,126 @@
+use revolt_http::RevoltHttp;
+use revolt_models::authentication::Authentication;
+use serde_json::Value;
+use std::collections::HashMap;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+    sync::mpsc,
+};
+
+/// Request as the mock server received it.
+#[derive(Debug)]
+pub struct Received {
+    pub method: String,
+    /// Path including the query
+    pub path: String,
+    pub headers: HashMap<String, String>,
+    /// JSON body, `Null` if there was none
+    pub body: Value,
+}
+
+/// Server that records every request and answers with an empty `204`.
+///
+/// Routes that return a body fail to deserialize it, only the request is checked for them.
+pub struct Mock {
+    /// Client sending to the server
+    pub http: RevoltHttp,
+    pub url: String,
+    requests: mpsc::UnboundedReceiver<Received>,
+}
+
+impl Mock {
+    pub async fn start() -> Self {
+        Mock::serve(true).await
+    }
+
+    /// Server that records every request and never answers.
+    pub async fn silent() -> Self {
+        Mock::serve(false).await
+    }
+
+    async fn serve(respond: bool) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, requests) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    let mut stream = BufReader::new(stream);
+                    while let Some(received) = read_request(&mut stream).await {
+                        sender.send(received).unwrap();
+                        if respond {
+                            stream
+                                .get_mut()
+                                .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+                                .await
+                                .unwrap();
+                        }
+                    }
+                });
+            }
+        });
+
+        let http =
+            RevoltHttp::new_base_url(Authentication::BotToken("token".to_string()), url.clone());
+        Mock {
+            http,
+            url,
+            requests,
+        }
+    }
+
+    /// Next request, whatever it is.
+    pub async fn received(&mut self) -> Received {
+        self.requests.recv().await.unwrap()
+    }
+
+    /// Next request, checked against the expected one.
+    pub async fn expect(&mut self, method: &str, path: &str, body: Value) -> Received {
+        let received = self.received().await;
+        assert_eq!(received.method, method);
+        assert_eq!(received.path, path);
+        assert_eq!(received.body, body, "body of {method} {path}");
+        assert_eq!(received.headers["x-bot-token"], "token");
+        received
+    }
+}
+
+async fn read_request(stream: &mut BufReader<tokio::net::TcpStream>) -> Option<Received> {
+    let mut line = String::new();
+    if stream.read_line(&mut line).await.ok()? == 0 {
+        return None;
+    }
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let path = parts.next()?.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        line.clear();
+        stream.read_line(&mut line).await.ok()?;
+        match line.trim_end().split_once(": ") {
+            Some((key, value)) => headers.insert(key.to_lowercase(), value.to_string()),
+            None => break,
+        };
+    }
+
+    let length = headers
+        .get("content-length")
+        .map_or(0, |length| length.parse().unwrap());
+    let mut body = vec![0; length];
+    stream.read_exact(&mut body).await.ok()?;
+    let body = match length {
+        0 => Value::Null,
+        _ => serde_json::from_slice(&body).unwrap(),
+    };
+
+    Some(Received {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
@@ -0,0 +1,88 @@
+#![allow(dead_code)]
+
+pub mod mock;
+
+use reqwest::{Request, Response};
+use revolt_http::{RetryPolicy, RevoltHttp, Transport, TransportError};
+use revolt_models::authentication::Authentication;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// Transport that records requests and answers them from a script.
+#[derive(Debug, Default)]
+pub struct Fake {
+    requests: Mutex<Vec<(String, String)>>,
+    times: Mutex<Vec<Instant>>,
+    responses: Mutex<VecDeque<Result<http::Response<String>, TransportError>>>,
+}
+
+impl Fake {
+    pub fn respond(self, status: u16, body: serde_json::Value) -> Self {
+        self.respond_with_headers(status, &[], body)
+    }
+
+    pub fn respond_with_headers(
+        self,
+        status: u16,
+        headers: &[(&str, &str)],
+        body: serde_json::Value,
+    ) -> Self {
+        let mut response = http::Response::builder().status(status);
+        for (name, value) in headers {
+            response = response.header(*name, *value);
+        }
+        let body = match body {
+            serde_json::Value::Null => String::new(),
+            body => body.to_string(),
+        };
+
+        let response = Ok(response.body(body).unwrap());
+        self.responses.lock().unwrap().push_back(response);
+        self
+    }
+
+    pub fn fail(self, err: TransportError) -> Self {
+        self.responses.lock().unwrap().push_back(Err(err));
+        self
+    }
+
+    /// Method and path of every request sent so far.
+    pub fn requests(&self) -> Vec<(String, String)> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// When every request was sent.
+    pub fn times(&self) -> Vec<Instant> {
+        self.times.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for Fake {
+    async fn send(&self, request: Request) -> Result<Response, TransportError> {
+        let route = (
+            request.method().to_string(),
+            request.url().path().to_string(),
+        );
+        self.requests.lock().unwrap().push(route);
+        self.times.lock().unwrap().push(Instant::now());
+
+        let response = self.responses.lock().unwrap().pop_front().unwrap()?;
+        Ok(Response::from(response))
+    }
+}
+
+/// Client that sends everything to the fake and retries without waiting.
+pub fn http(fake: Fake) -> (RevoltHttp, Arc<Fake>) {
+    let fake = Arc::new(fake);
+    let http = RevoltHttp::builder(Authentication::BotToken("token".to_string()))
+        .retry_policy(RetryPolicy::new().backoff(Duration::ZERO, Duration::ZERO))
+        .transport(fake.clone())
+        .build()
+        .unwrap();
+    (http, fake)
+}
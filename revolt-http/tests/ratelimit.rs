@@ -0,0 +1,108 @@
+mod common;
+
+use common::{http, Fake};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Headers of a response that used up a bucket allowing one request per minute.
+fn used_up(bucket: &'static str) -> [(&'static str, &'static str); 4] {
+    [
+        ("x-ratelimit-bucket", bucket),
+        ("x-ratelimit-limit", "1"),
+        ("x-ratelimit-remaining", "0"),
+        ("x-ratelimit-reset-after", "60000"),
+    ]
+}
+
+#[tokio::test(start_paused = true)]
+async fn channels_are_limited_separately() {
+    let (http, fake) = http(
+        Fake::default()
+            .respond_with_headers(204, &used_up("channel-a"), Value::Null)
+            .respond_with_headers(204, &used_up("channel-b"), Value::Null)
+            .respond(204, Value::Null),
+    );
+
+    http.delete_message("A", "M").await.unwrap();
+    http.delete_message("B", "M").await.unwrap();
+    http.delete_message("A", "M").await.unwrap();
+
+    let times = fake.times();
+    // B does not wait for the limit of A to reset, A does
+    assert!(times[1] - times[0] < Duration::from_secs(1));
+    assert!(times[2] - times[0] >= Duration::from_secs(60));
+
+    let mut buckets: Vec<_> = http.rate_limits().into_iter().map(|b| b.bucket).collect();
+    buckets.sort();
+    assert_eq!(buckets, ["channel-a", "channel-b"]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn rejected_request_is_sent_again_after_reset() {
+    let (http, fake) = http(
+        Fake::default()
+            .respond_with_headers(
+                429,
+                &[
+                    ("x-ratelimit-bucket", "channel-a"),
+                    ("x-ratelimit-limit", "10"),
+                    ("x-ratelimit-remaining", "0"),
+                    ("x-ratelimit-reset-after", "3000"),
+                ],
+                Value::Null,
+            )
+            .respond(200, Value::Null),
+    );
+
+    http.delete_message("A", "M").await.unwrap();
+
+    let times = fake.times();
+    assert_eq!(times.len(), 2);
+    assert!(times[1] - times[0] >= Duration::from_secs(3));
+}
+
+#[tokio::test(start_paused = true)]
+async fn rejected_request_without_bucket_waits_too() {
+    let (http, fake) = http(
+        Fake::default()
+            .respond_with_headers(429, &[("x-ratelimit-reset-after", "3000")], Value::Null)
+            .respond(200, Value::Null),
+    );
+
+    http.delete_message("A", "M").await.unwrap();
+
+    let times = fake.times();
+    assert_eq!(times.len(), 2);
+    assert!(times[1] - times[0] >= Duration::from_secs(3));
+    assert!(http.rate_limits().is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn rejected_request_waits_for_retry_after_in_body() {
+    let (http, fake) = http(
+        Fake::default()
+            .respond(429, json!({ "retry_after": 3000 }))
+            .respond(200, Value::Null),
+    );
+
+    http.delete_message("A", "M").await.unwrap();
+
+    let times = fake.times();
+    assert_eq!(times.len(), 2);
+    assert!(times[1] - times[0] >= Duration::from_secs(3));
+}
+
+#[tokio::test(start_paused = true)]
+async fn rejected_request_waits_for_retry_after_header() {
+    let (http, fake) = http(
+        Fake::default()
+            .respond_with_headers(429, &[("retry-after", "3")], Value::Null)
+            .respond(200, Value::Null),
+    );
+
+    http.delete_message("A", "M").await.unwrap();
+
+    let times = fake.times();
+    assert_eq!(times.len(), 2);
+    assert!(times[1] - times[0] >= Duration::from_secs(3));
+}
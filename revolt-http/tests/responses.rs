@@ -0,0 +1,169 @@
+mod common;
+
+use common::{http, Fake};
+use revolt_models::payload::{BanUserPayload, CreateRolePayload, FetchMembersPayload};
+use serde_json::json;
+
+const SERVER: &str = "01FVB1ZGCPS8TJ4PD4P7NAFDZA";
+const USER: &str = "01FVB1ZGCPS8TJ4PD4P7NAFDZB";
+
+fn avatar() -> serde_json::Value {
+    json!({
+        "_id": "p7Qo8QjSzAL4Yk2uQfYGBIdVnrqvEBaHxJHtDfpYrG",
+        "tag": "avatars",
+        "filename": "avatar.png",
+        "metadata": { "type": "Image", "width": 256, "height": 256 },
+        "content_type": "image/png",
+        "size": 48213
+    })
+}
+
+#[tokio::test]
+async fn fetch_bans() {
+    let (http, fake) = http(Fake::default().respond(
+        200,
+        json!({
+            "users": [
+                { "_id": USER, "username": "spammer", "avatar": avatar() },
+                { "_id": "01FVB1ZGCPS8TJ4PD4P7NAFDZC", "username": "troll", "avatar": null }
+            ],
+            "bans": [
+                { "_id": { "server": SERVER, "user": USER }, "reason": "Spam" },
+                { "_id": { "server": SERVER, "user": "01FVB1ZGCPS8TJ4PD4P7NAFDZC" } }
+            ]
+        }),
+    ));
+
+    let response = http.fetch_bans(SERVER).await.unwrap();
+
+    assert_eq!(response.users.len(), 2);
+    assert_eq!(response.users[0].id, USER);
+    assert_eq!(response.users[0].username, "spammer");
+    assert_eq!(response.users[0].avatar.as_ref().unwrap().size, 48213);
+    assert!(response.users[1].avatar.is_none());
+
+    assert_eq!(response.bans[0].id.server, SERVER);
+    assert_eq!(response.bans[0].id.user, USER);
+    assert_eq!(response.bans[0].reason.as_deref(), Some("Spam"));
+    assert_eq!(response.bans[1].reason, None);
+
+    assert_eq!(
+        fake.requests(),
+        [("GET".to_string(), format!("/servers/{SERVER}/bans"))]
+    );
+}
+
+#[tokio::test]
+async fn ban_user() {
+    let (http, _) = http(Fake::default().respond(
+        200,
+        json!({ "_id": { "server": SERVER, "user": USER }, "reason": "Spam" }),
+    ));
+
+    let ban = http
+        .ban_user(
+            SERVER,
+            USER,
+            BanUserPayload {
+                reason: Some("Spam".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(ban.id.server, SERVER);
+    assert_eq!(ban.id.user, USER);
+    assert_eq!(ban.reason.as_deref(), Some("Spam"));
+}
+
+#[tokio::test]
+async fn fetch_members() {
+    let (http, _) = http(Fake::default().respond(
+        200,
+        json!({
+            "members": [
+                {
+                    "_id": { "server": SERVER, "user": USER },
+                    "joined_at": "2022-07-01T12:00:00.000Z",
+                    "nickname": "Nick",
+                    "avatar": avatar(),
+                    "roles": ["01FVB1ZGCPS8TJ4PD4P7NAFDZD"]
+                },
+                {
+                    "_id": { "server": SERVER, "user": "01FVB1ZGCPS8TJ4PD4P7NAFDZC" },
+                    "joined_at": "2022-07-02T08:30:00.000Z"
+                }
+            ],
+            "users": [
+                {
+                    "_id": USER,
+                    "username": "someone",
+                    "avatar": avatar(),
+                    "badges": 0,
+                    "status": { "text": "Hello", "presence": "Online" },
+                    "relationship": "None",
+                    "online": true
+                },
+                {
+                    "_id": "01FVB1ZGCPS8TJ4PD4P7NAFDZC",
+                    "username": "bot",
+                    "bot": { "owner": USER },
+                    "relationship": "None",
+                    "online": false
+                }
+            ]
+        }),
+    ));
+
+    let response = http
+        .fetch_members(
+            SERVER,
+            FetchMembersPayload {
+                exclude_offline: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.members.len(), 2);
+    assert_eq!(response.members[0].id.user, USER);
+    assert_eq!(response.members[0].nickname.as_deref(), Some("Nick"));
+    assert_eq!(response.members[0].roles, ["01FVB1ZGCPS8TJ4PD4P7NAFDZD"]);
+    assert!(response.members[1].roles.is_empty());
+
+    assert_eq!(response.users.len(), 2);
+    assert_eq!(response.users[0].username, "someone");
+    assert_eq!(response.users[1].bot.as_ref().unwrap().owner, USER);
+}
+
+#[tokio::test]
+async fn create_role() {
+    let (http, _) = http(Fake::default().respond(
+        200,
+        json!({
+            "id": "01FVB1ZGCPS8TJ4PD4P7NAFDZD",
+            "role": {
+                "name": "Moderator",
+                "permissions": { "a": 0, "d": 0 },
+                "rank": 2
+            }
+        }),
+    ));
+
+    let response = http
+        .create_role(
+            SERVER,
+            CreateRolePayload {
+                name: "Moderator".to_string(),
+                rank: Some(2),
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.id, "01FVB1ZGCPS8TJ4PD4P7NAFDZD");
+    assert_eq!(response.role.name, "Moderator");
+    assert_eq!(response.role.rank, 2);
+    assert_eq!(response.role.colour, None);
+    assert!(!response.role.hoist);
+}
@@ -0,0 +1,567 @@
+mod common;
+
+use common::mock::Mock;
+use revolt_models::{
+    emoji::EmojiParent,
+    member::FieldsMember,
+    message::MessageSort,
+    payload::{
+        BanUserPayload, BulkDeleteMessagesPayload, ChangeUsernamePayload, CreateBotPayload,
+        CreateChannelPayload, CreateEmojiPayload, CreateGroupPayload, CreateRolePayload,
+        CreateServerPayload, EditBotPayload, EditChannelPayload, EditMemberPayload,
+        EditMessagePayload, EditRolePayload, EditServerPayload, EditUserPayload,
+        FetchMembersPayload, FetchMessagesPayload, InviteBotPayload,
+        RemoveReactionToMessagePayload, SearchForMessagesPayload, SendFriendRequestPayload,
+        SendMessagePayload, SetDefaultPermissionPayload, SetDefaultServerPermissionPayload,
+        SetRolePermissionPayload,
+    },
+    permission::{Override, Permission},
+    server::FieldsRole,
+};
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn core() {
+    let mut mock = Mock::start().await;
+
+    let _ = mock.http.query_node().await;
+    mock.expect("GET", "/", Value::Null).await;
+}
+
+#[tokio::test]
+async fn bots() {
+    let mut mock = Mock::start().await;
+
+    let create = CreateBotPayload {
+        name: "name".to_string(),
+    };
+    let _ = mock.http.create_bot(create).await;
+    mock.expect("POST", "/bots/create", json!({ "name": "name" }))
+        .await;
+
+    let _ = mock.http.fetch_public_bot("B").await;
+    mock.expect("GET", "/bots/B/invite", Value::Null).await;
+
+    let invite = InviteBotPayload::Server {
+        server: "S".to_string(),
+    };
+    mock.http.invite_bot("B", invite).await.unwrap();
+    mock.expect("POST", "/bots/B/invite", json!({ "server": "S" }))
+        .await;
+
+    let _ = mock.http.fetch_bot("B").await;
+    mock.expect("GET", "/bots/B", Value::Null).await;
+
+    mock.http.delete_bot("B").await.unwrap();
+    mock.expect("DELETE", "/bots/B", Value::Null).await;
+
+    let edit = EditBotPayload {
+        name: Some("name".to_string()),
+        public: None,
+        analytics: None,
+        interactions_url: None,
+        remove: None,
+    };
+    let _ = mock.http.edit_bot("B", edit).await;
+    mock.expect("PATCH", "/bots/B", json!({ "name": "name" }))
+        .await;
+
+    let _ = mock.http.fetch_owned_bots().await;
+    mock.expect("GET", "/bots/@me", Value::Null).await;
+}
+
+#[tokio::test]
+async fn channels() {
+    let mut mock = Mock::start().await;
+
+    let _ = mock.http.fetch_channel("C").await;
+    mock.expect("GET", "/channels/C", Value::Null).await;
+
+    mock.http.close_channel("C").await.unwrap();
+    mock.expect("DELETE", "/channels/C", Value::Null).await;
+
+    let edit = EditChannelPayload {
+        name: Some("name".to_string()),
+        description: None,
+        owner: None,
+        icon: None,
+        nsfw: Some(true),
+        remove: None,
+    };
+    let _ = mock.http.edit_channel("C", edit).await;
+    mock.expect(
+        "PATCH",
+        "/channels/C",
+        json!({ "name": "name", "nsfw": true }),
+    )
+    .await;
+
+    let _ = mock.http.create_invite("C").await;
+    mock.expect("POST", "/channels/C/invites", Value::Null)
+        .await;
+
+    let permissions = SetRolePermissionPayload {
+        permissions: Override {
+            allow: Permission::ManageChannel,
+            deny: Permission::ManageServer,
+        },
+    };
+    let _ = mock
+        .http
+        .set_role_channel_permissions("C", "R", permissions)
+        .await;
+    mock.expect(
+        "PUT",
+        "/channels/C/permissions/R",
+        json!({ "permissions": { "allow": 1, "deny": 2 } }),
+    )
+    .await;
+
+    let permissions = SetDefaultPermissionPayload::Value {
+        permissions: Permission::ManageChannel | Permission::ManageServer,
+    };
+    let _ = mock
+        .http
+        .set_default_channel_permissions("C", permissions)
+        .await;
+    mock.expect(
+        "PUT",
+        "/channels/C/permissions/default",
+        json!({ "permissions": 3 }),
+    )
+    .await;
+
+    let _ = mock.http.join_call("C").await;
+    mock.expect("POST", "/channels/C/join_call", Value::Null)
+        .await;
+}
+
+#[tokio::test]
+async fn groups() {
+    let mut mock = Mock::start().await;
+
+    let create = CreateGroupPayload {
+        name: "name".to_string(),
+        description: None,
+        users: vec!["U".to_string()],
+        nsfw: None,
+    };
+    let _ = mock.http.create_group(create).await;
+    mock.expect(
+        "POST",
+        "/channels/create",
+        json!({ "name": "name", "description": null, "users": ["U"] }),
+    )
+    .await;
+
+    let _ = mock.http.fetch_group_members("G").await;
+    mock.expect("GET", "/channels/G/members", Value::Null).await;
+
+    mock.http.add_member_to_group("G", "U").await.unwrap();
+    mock.expect("PUT", "/channels/G/recipients/U", Value::Null)
+        .await;
+
+    mock.http.remove_member_from_group("G", "U").await.unwrap();
+    mock.expect("DELETE", "/channels/G/recipients/U", Value::Null)
+        .await;
+}
+
+#[tokio::test]
+async fn messaging() {
+    let mut mock = Mock::start().await;
+
+    mock.http.acknowledge_message("C", "M").await.unwrap();
+    mock.expect("PUT", "/channels/C/ack/M", Value::Null).await;
+
+    let fetch = FetchMessagesPayload {
+        limit: Some(10),
+        before: None,
+        after: Some("M".to_string()),
+        sort: None,
+        nearby: None,
+        include_users: None,
+    };
+    let _ = mock.http.fetch_messages("C", fetch).await;
+    mock.expect("GET", "/channels/C/messages?limit=10&after=M", Value::Null)
+        .await;
+
+    let send = SendMessagePayload {
+        content: Some("hello".to_string()),
+        attachments: None,
+        replies: None,
+        embeds: None,
+        masquerade: None,
+        interactions: None,
+        nonce: Some("nonce".to_string()),
+    };
+    let _ = mock.http.send_message("C", send).await;
+    let received = mock
+        .expect(
+            "POST",
+            "/channels/C/messages",
+            json!({ "content": "hello" }),
+        )
+        .await;
+    assert_eq!(received.headers["idempotency-key"], "nonce");
+
+    let search = SearchForMessagesPayload {
+        query: "hello".to_string(),
+        limit: None,
+        before: None,
+        after: None,
+        sort: Some(MessageSort::Latest),
+        include_users: None,
+    };
+    let _ = mock.http.search_for_messages("C", search).await;
+    mock.expect(
+        "POST",
+        "/channels/C/messages/search",
+        json!({ "query": "hello", "sort": "Latest" }),
+    )
+    .await;
+
+    let _ = mock.http.fetch_message("C", "M").await;
+    mock.expect("GET", "/channels/C/messages/M", Value::Null)
+        .await;
+
+    mock.http.delete_message("C", "M").await.unwrap();
+    mock.expect("DELETE", "/channels/C/messages/M", Value::Null)
+        .await;
+
+    let edit = EditMessagePayload {
+        content: Some("edited".to_string()),
+        embeds: None,
+    };
+    let _ = mock.http.edit_message("C", "M", edit).await;
+    mock.expect(
+        "PATCH",
+        "/channels/C/messages/M",
+        json!({ "content": "edited" }),
+    )
+    .await;
+
+    let bulk = BulkDeleteMessagesPayload {
+        ids: vec!["M".to_string(), "N".to_string()],
+    };
+    mock.http.bulk_delete_messages("C", bulk).await.unwrap();
+    mock.expect(
+        "DELETE",
+        "/channels/C/messages/bulk",
+        json!({ "ids": ["M", "N"] }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn interactions() {
+    let mut mock = Mock::start().await;
+
+    mock.http
+        .add_reaction_to_message("C", "M", "E")
+        .await
+        .unwrap();
+    mock.expect("PUT", "/channels/C/messages/M/reactions/E", Value::Null)
+        .await;
+
+    let remove = RemoveReactionToMessagePayload {
+        user_id: Some("U".to_string()),
+        remove_all: None,
+    };
+    mock.http
+        .remove_reaction_to_message("C", "M", "E", remove)
+        .await
+        .unwrap();
+    mock.expect(
+        "DELETE",
+        "/channels/C/messages/M/reactions/E?user_id=U",
+        Value::Null,
+    )
+    .await;
+
+    mock.http
+        .remove_all_reactions_from_message("C", "M")
+        .await
+        .unwrap();
+    mock.expect("DELETE", "/channels/C/messages/M/reactions", Value::Null)
+        .await;
+}
+
+#[tokio::test]
+async fn servers() {
+    let mut mock = Mock::start().await;
+
+    let create = CreateServerPayload {
+        name: "name".to_string(),
+        description: None,
+        nsfw: None,
+    };
+    let _ = mock.http.create_server(create).await;
+    mock.expect(
+        "POST",
+        "/servers/create",
+        json!({ "name": "name", "description": null }),
+    )
+    .await;
+
+    let _ = mock.http.fetch_server("S").await;
+    mock.expect("GET", "/servers/S", Value::Null).await;
+
+    mock.http.delete_or_leave_server("S").await.unwrap();
+    mock.expect("DELETE", "/servers/S", Value::Null).await;
+
+    let edit = EditServerPayload {
+        name: Some("name".to_string()),
+        description: None,
+        icon: None,
+        banner: None,
+        categories: None,
+        system_messages: None,
+        nsfw: None,
+        discoverable: None,
+        analytics: None,
+        remove: None,
+    };
+    let _ = mock.http.edit_server("S", edit).await;
+    let received = mock.received().await;
+    assert_eq!(
+        (&*received.method, &*received.path),
+        ("PATCH", "/servers/S")
+    );
+    assert_eq!(received.body["name"], "name");
+
+    mock.http.mark_server_as_read("S").await.unwrap();
+    mock.expect("PUT", "/servers/S/ack", Value::Null).await;
+
+    let channel = CreateChannelPayload {
+        channel_type: Default::default(),
+        name: "name".to_string(),
+        description: None,
+        nsfw: None,
+    };
+    let _ = mock.http.create_channel("S", channel).await;
+    mock.expect(
+        "POST",
+        "/servers/S/channels",
+        json!({ "type": "Text", "name": "name", "description": null }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn members() {
+    let mut mock = Mock::start().await;
+
+    let _ = mock.http.fetch_member("S", "U").await;
+    mock.expect("GET", "/servers/S/members/U", Value::Null)
+        .await;
+
+    let fetch = FetchMembersPayload {
+        exclude_offline: Some(true),
+    };
+    let _ = mock.http.fetch_members("S", fetch).await;
+    mock.expect(
+        "GET",
+        "/servers/S/members?exclude_offline=true",
+        Value::Null,
+    )
+    .await;
+
+    let edit = EditMemberPayload {
+        nickname: Some("nickname".to_string()),
+        avatar: None,
+        roles: Some(vec!["R".to_string()]),
+        timeout: None,
+        remove: Some(vec![FieldsMember::Avatar]),
+    };
+    let _ = mock.http.edit_member("S", "U", edit).await;
+    mock.expect(
+        "PATCH",
+        "/servers/S/members/U",
+        json!({ "nickname": "nickname", "roles": ["R"], "remove": ["Avatar"] }),
+    )
+    .await;
+
+    mock.http.kick_member("S", "U").await.unwrap();
+    mock.expect("DELETE", "/servers/S/members/U", Value::Null)
+        .await;
+}
+
+#[tokio::test]
+async fn bans() {
+    let mut mock = Mock::start().await;
+
+    let ban = BanUserPayload {
+        reason: Some("spam".to_string()),
+    };
+    let _ = mock.http.ban_user("S", "U", ban).await;
+    mock.expect("PUT", "/servers/S/bans/U", json!({ "reason": "spam" }))
+        .await;
+
+    mock.http.unban_user("S", "U").await.unwrap();
+    mock.expect("DELETE", "/servers/S/bans/U", Value::Null)
+        .await;
+
+    let _ = mock.http.fetch_bans("S").await;
+    mock.expect("GET", "/servers/S/bans", Value::Null).await;
+}
+
+#[tokio::test]
+async fn roles() {
+    let mut mock = Mock::start().await;
+
+    let create = CreateRolePayload {
+        name: "name".to_string(),
+        rank: None,
+    };
+    let _ = mock.http.create_role("S", create).await;
+    mock.expect("POST", "/servers/S/roles", json!({ "name": "name" }))
+        .await;
+
+    let edit = EditRolePayload {
+        name: None,
+        colour: Some("red".to_string()),
+        hoist: Some(true),
+        rank: Some(1),
+        remove: Some(vec![FieldsRole::Colour]),
+    };
+    let _ = mock.http.edit_role("S", "R", edit).await;
+    mock.expect(
+        "PATCH",
+        "/servers/S/roles/R",
+        json!({ "colour": "red", "hoist": true, "rank": 1, "remove": ["Colour"] }),
+    )
+    .await;
+
+    mock.http.delete_role("S", "R").await.unwrap();
+    mock.expect("DELETE", "/servers/S/roles/R", Value::Null)
+        .await;
+
+    let permissions = SetRolePermissionPayload {
+        permissions: Override {
+            allow: Permission::ManageChannel,
+            deny: Permission::ManageServer,
+        },
+    };
+    let _ = mock
+        .http
+        .set_role_server_permissions("S", "R", permissions)
+        .await;
+    mock.expect(
+        "PUT",
+        "/servers/S/permissions/R",
+        json!({ "permissions": { "allow": 1, "deny": 2 } }),
+    )
+    .await;
+
+    let permissions = SetDefaultServerPermissionPayload {
+        permissions: Permission::ManageChannel | Permission::ManageServer,
+    };
+    let _ = mock
+        .http
+        .set_default_server_permissions("S", permissions)
+        .await;
+    mock.expect(
+        "PUT",
+        "/servers/S/permissions/default",
+        json!({ "permissions": 3 }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn emojis() {
+    let mut mock = Mock::start().await;
+
+    let _ = mock.http.fetch_emoji("E").await;
+    mock.expect("GET", "/custom/emoji/E", Value::Null).await;
+
+    let create = CreateEmojiPayload {
+        name: "name".to_string(),
+        parent: EmojiParent::Server {
+            id: "S".to_string(),
+        },
+        nsfw: Some(false),
+    };
+    let _ = mock.http.create_emoji("E", create).await;
+    mock.expect(
+        "PUT",
+        "/custom/emoji/E",
+        json!({ "name": "name", "parent": { "type": "Server", "id": "S" }, "nsfw": false }),
+    )
+    .await;
+
+    mock.http.delete_emoji("E").await.unwrap();
+    mock.expect("DELETE", "/custom/emoji/E", Value::Null).await;
+
+    let _ = mock.http.fetch_server_emojis("S").await;
+    mock.expect("GET", "/servers/S/emojis", Value::Null).await;
+}
+
+#[tokio::test]
+async fn users() {
+    let mut mock = Mock::start().await;
+
+    let _ = mock.http.fetch_self().await;
+    mock.expect("GET", "/users/@me", Value::Null).await;
+
+    let edit = EditUserPayload {
+        status: None,
+        profile: None,
+        avatar: Some("A".to_string()),
+        remove: None,
+    };
+    let _ = mock.http.edit_user(edit).await;
+    mock.expect("PATCH", "/users/@me", json!({ "avatar": "A" }))
+        .await;
+
+    let _ = mock.http.fetch_user("U").await;
+    mock.expect("GET", "/users/U", Value::Null).await;
+
+    let username = ChangeUsernamePayload {
+        username: "name".to_string(),
+        password: "password".to_string(),
+    };
+    let _ = mock.http.change_username(username).await;
+    mock.expect(
+        "PATCH",
+        "/users/@me/username",
+        json!({ "username": "name", "password": "password" }),
+    )
+    .await;
+
+    let avatar = mock.http.fetch_default_avatar("U").await.unwrap();
+    assert!(avatar.is_empty());
+    mock.expect("GET", "/users/U/default_avatar", Value::Null)
+        .await;
+
+    let _ = mock.http.fetch_user_profile("U").await;
+    mock.expect("GET", "/users/U/profile", Value::Null).await;
+
+    let _ = mock.http.fetch_direct_message_channels().await;
+    mock.expect("GET", "/users/dms", Value::Null).await;
+
+    let _ = mock.http.open_direct_message("U").await;
+    mock.expect("GET", "/users/U/dm", Value::Null).await;
+
+    let _ = mock.http.fetch_mutual_friends_and_servers("U").await;
+    mock.expect("GET", "/users/U/mutual", Value::Null).await;
+
+    let _ = mock.http.accept_friend_request("U").await;
+    mock.expect("PUT", "/users/U/friend", Value::Null).await;
+
+    let _ = mock.http.remove_or_deny_friend("U").await;
+    mock.expect("DELETE", "/users/U/friend", Value::Null).await;
+
+    let _ = mock.http.block_user("U").await;
+    mock.expect("PUT", "/users/U/block", Value::Null).await;
+
+    let _ = mock.http.unblock_user("U").await;
+    mock.expect("DELETE", "/users/U/block", Value::Null).await;
+
+    let friend = SendFriendRequestPayload {
+        username: "name".to_string(),
+    };
+    let _ = mock.http.send_friend_request(friend).await;
+    mock.expect("POST", "/users/friend", json!({ "username": "name" }))
+        .await;
+}
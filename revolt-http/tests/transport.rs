@@ -0,0 +1,69 @@
+mod common;
+
+use common::{http, Fake};
+use revolt_http::{RevoltHttpError, TransportError};
+use revolt_models::payload::EditBotPayload;
+use serde_json::json;
+
+#[tokio::test]
+async fn canned_response() {
+    let (http, fake) = http(Fake::default().respond(
+        200,
+        json!({
+            "_id": "B",
+            "owner": "U",
+            "token": "secret",
+            "public": false,
+        }),
+    ));
+
+    let edit = EditBotPayload {
+        name: None,
+        public: Some(false),
+        analytics: None,
+        interactions_url: None,
+        remove: None,
+    };
+    let bot = http.edit_bot("B", edit).await.unwrap();
+
+    assert_eq!(bot.id, "B");
+    assert_eq!(
+        fake.requests(),
+        [("PATCH".to_string(), "/bots/B".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn api_error() {
+    let (http, _) = http(Fake::default().respond(404, json!({ "type": "NotFound" })));
+
+    let err = http.delete_bot("B").await.unwrap_err();
+
+    assert_eq!(err.api_error(), Some(&revolt_models::ApiError::NotFound));
+}
+
+#[tokio::test]
+async fn retries_transport_errors() {
+    let (http, fake) = http(
+        Fake::default()
+            .fail(TransportError::Connect("refused".into()))
+            .respond(204, json!(null)),
+    );
+
+    http.delete_bot("B").await.unwrap();
+
+    assert_eq!(fake.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn other_errors_are_not_retried() {
+    let (http, fake) = http(Fake::default().fail(TransportError::Other("broken".into())));
+
+    let err = http.delete_bot("B").await.unwrap_err();
+
+    assert!(matches!(
+        err,
+        RevoltHttpError::Transport(TransportError::Other(_))
+    ));
+    assert_eq!(fake.requests().len(), 1);
+}
@@ -1,8 +1,8 @@
 use crate::permission::{Permission, UserPermission};
-use serde::Deserialize;
+use serde::{de::Error as _, Deserialize, Deserializer};
+use serde_json::Value;
 
-#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
-#[serde(tag = "type")]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum ApiError {
     LabelMe,
 
@@ -30,7 +30,9 @@ pub enum ApiError {
     EmptyMessage,
     PayloadTooLarge,
     CannotRemoveYourself,
-    GroupTooLarge { max: usize },
+    GroupTooLarge {
+        max: usize,
+    },
     AlreadyInGroup,
     NotInGroup,
 
@@ -38,7 +40,9 @@ pub enum ApiError {
     UnknownServer,
     InvalidRole,
     Banned,
-    TooManyServers { max: usize },
+    TooManyServers {
+        max: usize,
+    },
     TooManyEmoji,
 
     // Bot related errors
@@ -47,14 +51,21 @@ pub enum ApiError {
     BotIsPrivate,
 
     // Permission errors
-    MissingPermission { permission: Permission },
-    MissingUserPermission { permission: UserPermission },
+    MissingPermission {
+        permission: Permission,
+    },
+    MissingUserPermission {
+        permission: UserPermission,
+    },
     NotElevated,
     CannotGiveMissingPermissions,
     NotOwner,
 
     // General errors
-    DatabaseError { operation: String, with: String },
+    DatabaseError {
+        operation: String,
+        with: String,
+    },
     InternalError,
     InvalidOperation,
     InvalidCredentials,
@@ -67,4 +78,137 @@ pub enum ApiError {
 
     // Other errors that API does not return but it's still API related things
     Unauthenticated,
+
+    /// Error this version of the crate does not know about
+    Unknown {
+        /// Value of the `type` field
+        error_type: String,
+        /// Whole error as received, in JSON
+        raw: String,
+    },
+}
+
+/// Derived implementation for the error types this crate knows.
+#[derive(Deserialize)]
+#[serde(tag = "type", remote = "ApiError")]
+enum Tagged {
+    LabelMe,
+    AlreadyOnboarded,
+    UsernameTaken,
+    InvalidUsername,
+    UnknownUser,
+    AlreadyFriends,
+    AlreadySentRequest,
+    Blocked,
+    BlockedByOther,
+    NotFriends,
+    UnknownChannel,
+    UnknownAttachment,
+    UnknownMessage,
+    CannotEditMessage,
+    CannotJoinCall,
+    TooManyAttachments,
+    TooManyReplies,
+    EmptyMessage,
+    PayloadTooLarge,
+    CannotRemoveYourself,
+    GroupTooLarge { max: usize },
+    AlreadyInGroup,
+    NotInGroup,
+    UnknownServer,
+    InvalidRole,
+    Banned,
+    TooManyServers { max: usize },
+    TooManyEmoji,
+    ReachedMaximumBots,
+    IsBot,
+    BotIsPrivate,
+    MissingPermission { permission: Permission },
+    MissingUserPermission { permission: UserPermission },
+    NotElevated,
+    CannotGiveMissingPermissions,
+    NotOwner,
+    DatabaseError { operation: String, with: String },
+    InternalError,
+    InvalidOperation,
+    InvalidCredentials,
+    InvalidSession,
+    DuplicateNonce,
+    VosoUnavailable,
+    NotFound,
+    NoEffect,
+    FailedValidation,
+    Unauthenticated,
+}
+
+known_types!(
+    /// Error types deserialized into their own [ApiError] variant.
+    KNOWN_ERROR_TYPES: ApiError, Unknown, Tagged, [
+        LabelMe,
+        AlreadyOnboarded,
+        UsernameTaken,
+        InvalidUsername,
+        UnknownUser,
+        AlreadyFriends,
+        AlreadySentRequest,
+        Blocked,
+        BlockedByOther,
+        NotFriends,
+        UnknownChannel,
+        UnknownAttachment,
+        UnknownMessage,
+        CannotEditMessage,
+        CannotJoinCall,
+        TooManyAttachments,
+        TooManyReplies,
+        EmptyMessage,
+        PayloadTooLarge,
+        CannotRemoveYourself,
+        GroupTooLarge,
+        AlreadyInGroup,
+        NotInGroup,
+        UnknownServer,
+        InvalidRole,
+        Banned,
+        TooManyServers,
+        TooManyEmoji,
+        ReachedMaximumBots,
+        IsBot,
+        BotIsPrivate,
+        MissingPermission,
+        MissingUserPermission,
+        NotElevated,
+        CannotGiveMissingPermissions,
+        NotOwner,
+        DatabaseError,
+        InternalError,
+        InvalidOperation,
+        InvalidCredentials,
+        InvalidSession,
+        DuplicateNonce,
+        VosoUnavailable,
+        NotFound,
+        NoEffect,
+        FailedValidation,
+        Unauthenticated,
+    ]
+);
+
+impl<'de> Deserialize<'de> for ApiError {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = Value::deserialize(deserializer)?;
+        let error_type = raw
+            .get("type")
+            .and_then(Value::as_str)
+            .ok_or_else(|| D::Error::missing_field("type"))?;
+
+        if KNOWN_ERROR_TYPES.contains(&error_type) {
+            Tagged::deserialize(raw).map_err(D::Error::custom)
+        } else {
+            Ok(ApiError::Unknown {
+                error_type: error_type.to_string(),
+                raw: raw.to_string(),
+            })
+        }
+    }
 }
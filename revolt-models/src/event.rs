@@ -1,4 +1,5 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 
 use crate::{
     channel::{Channel, FieldsChannel, PartialChannel},
@@ -20,8 +21,7 @@ pub enum ErrorId {
     AlreadyAuthenticated,
 }
 
-#[derive(Deserialize, Debug, Clone)]
-#[serde(tag = "type")]
+#[derive(Debug, Clone)]
 pub enum ServerToClientEvent {
     /// Multiple events
     Bulk { v: Vec<ServerToClientEvent> },
@@ -45,10 +45,7 @@ pub enum ServerToClientEvent {
     Pong { data: u32 },
 
     /// New message
-    Message {
-        #[serde(flatten)]
-        message: Message,
-    },
+    Message { message: Message },
 
     /// Update existing message
     MessageUpdate {
@@ -189,15 +186,256 @@ pub enum ServerToClientEvent {
 
     /// Delete emoji
     EmojiDelete { id: String },
-    #[serde(other)]
-    Unknown,
+
+    /// Event this version of the crate does not know about
+    Unknown {
+        /// Value of the `type` field
+        event_type: String,
+        /// Whole event as received
+        raw: Value,
+    },
+}
+
+/// Derived implementation for the event types this crate knows, see [KnownEvent].
+#[derive(Deserialize)]
+#[serde(tag = "type", remote = "ServerToClientEvent")]
+enum Tagged {
+    Bulk {
+        v: Vec<ServerToClientEvent>,
+    },
+    Error {
+        error: ErrorId,
+    },
+    Authenticated,
+    Ready {
+        users: Vec<User>,
+        servers: Vec<Server>,
+        channels: Vec<Channel>,
+        members: Vec<Member>,
+        emojis: Option<Vec<Emoji>>,
+    },
+    Pong {
+        data: u32,
+    },
+    Message {
+        #[serde(flatten)]
+        message: Message,
+    },
+    MessageUpdate {
+        id: String,
+        channel: String,
+        data: PartialMessage,
+    },
+    MessageAppend {
+        id: String,
+        channel: String,
+        append: AppendMessage,
+    },
+    MessageDelete {
+        id: String,
+        channel: String,
+    },
+    MessageReact {
+        id: String,
+        channel_id: String,
+        user_id: String,
+        emoji_id: String,
+    },
+    MessageUnreact {
+        id: String,
+        channel_id: String,
+        user_id: String,
+        emoji_id: String,
+    },
+    MessageRemoveReaction {
+        id: String,
+        channel_id: String,
+        emoji_id: String,
+    },
+    BulkMessageDelete {
+        channel: String,
+        ids: Vec<String>,
+    },
+    ChannelCreate(Channel),
+    ChannelUpdate {
+        id: String,
+        data: PartialChannel,
+        clear: Vec<FieldsChannel>,
+    },
+    ChannelDelete {
+        id: String,
+    },
+    ChannelGroupJoin {
+        id: String,
+        user: String,
+    },
+    ChannelGroupLeave {
+        id: String,
+        user: String,
+    },
+    ChannelStartTyping {
+        id: String,
+        user: String,
+    },
+    ChannelStopTyping {
+        id: String,
+        user: String,
+    },
+    ChannelAck {
+        id: String,
+        user: String,
+        message_id: String,
+    },
+    ServerCreate {
+        id: String,
+        server: Server,
+        channels: Vec<Channel>,
+    },
+    ServerUpdate {
+        id: String,
+        data: PartialServer,
+        clear: Vec<FieldsServer>,
+    },
+    ServerDelete {
+        id: String,
+    },
+    ServerMemberUpdate {
+        id: MemberCompositeKey,
+        data: PartialMember,
+        clear: Vec<FieldsMember>,
+    },
+    ServerMemberJoin {
+        id: String,
+        user: String,
+    },
+    ServerMemberLeave {
+        id: String,
+        user: String,
+    },
+    ServerRoleUpdate {
+        id: String,
+        role_id: String,
+        data: PartialRole,
+        clear: Vec<FieldsRole>,
+    },
+    ServerRoleDelete {
+        id: String,
+        role_id: String,
+    },
+    UserUpdate {
+        id: String,
+        data: PartialUser,
+        clear: Vec<FieldsUser>,
+    },
+    UserRelationship {
+        id: String,
+        user: User,
+        status: RelationshipStatus,
+    },
+    UserSettingsUpdate {
+        id: String,
+        update: UserSettings,
+    },
+    EmojiCreate(Emoji),
+    EmojiDelete {
+        id: String,
+    },
+}
+
+known_types!(
+    /// Event types deserialized into their own [ServerToClientEvent] variant.
+    KNOWN_EVENT_TYPES: ServerToClientEvent, Unknown, Tagged, [
+        Bulk,
+        Error,
+        Authenticated,
+        Ready,
+        Pong,
+        Message,
+        MessageUpdate,
+        MessageAppend,
+        MessageDelete,
+        MessageReact,
+        MessageUnreact,
+        MessageRemoveReaction,
+        BulkMessageDelete,
+        ChannelCreate,
+        ChannelUpdate,
+        ChannelDelete,
+        ChannelGroupJoin,
+        ChannelGroupLeave,
+        ChannelStartTyping,
+        ChannelStopTyping,
+        ChannelAck,
+        ServerCreate,
+        ServerUpdate,
+        ServerDelete,
+        ServerMemberUpdate,
+        ServerMemberJoin,
+        ServerMemberLeave,
+        ServerRoleUpdate,
+        ServerRoleDelete,
+        UserUpdate,
+        UserRelationship,
+        UserSettingsUpdate,
+        EmojiCreate,
+        EmojiDelete,
+    ]
+);
+
+/// Events of unknown types are kept as [ServerToClientEvent::Unknown] together
+/// with their raw data, so every event is buffered before it is decoded.
+/// [KnownEvent] decodes known types straight from the input instead.
+impl<'de> Deserialize<'de> for ServerToClientEvent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = Value::deserialize(deserializer)?;
+        let event_type = raw
+            .get("type")
+            .and_then(Value::as_str)
+            .ok_or_else(|| D::Error::missing_field("type"))?;
+
+        if KNOWN_EVENT_TYPES.contains(&event_type) {
+            Tagged::deserialize(raw).map_err(D::Error::custom)
+        } else {
+            Ok(ServerToClientEvent::Unknown {
+                event_type: event_type.to_string(),
+                raw,
+            })
+        }
+    }
+}
+
+/// Event of a type this version of the crate knows.
+///
+/// Deserializing fails for other types instead of falling back to
+/// [ServerToClientEvent::Unknown], in turn the event is decoded straight
+/// from the input. Decode the input as a [ServerToClientEvent] again if
+/// it fails, to tell unknown events from broken ones.
+#[derive(Debug, Clone)]
+pub struct KnownEvent(pub ServerToClientEvent);
+
+impl<'de> Deserialize<'de> for KnownEvent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Tagged::deserialize(deserializer).map(KnownEvent)
+    }
 }
 
 #[derive(Serialize, Debug, Clone, Eq, PartialEq)]
 #[serde(tag = "type")]
 pub enum ClientToServerEvent {
+    /// Authenticate with the events server
     Authenticate { token: String },
+
+    /// Ping the server, it responds with a pong carrying the same data
     Ping { data: i32 },
+
+    /// Let others know we started typing in a channel
     BeginTyping { channel: String },
+
+    /// Let others know we stopped typing in a channel
     EndTyping { channel: String },
+
+    /// Subscribe to member presence updates of a server
+    ///
+    /// The subscription lasts for 15 minutes and has to be renewed to continue.
+    Subscribe { server_id: String },
 }
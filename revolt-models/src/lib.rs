@@ -1,3 +1,6 @@
+#[macro_use]
+mod macros;
+
 pub mod attachment;
 pub mod authentication;
 pub mod bot;
@@ -0,0 +1,25 @@
+/// Declare the `type` tags deserialized into their own variant of an enum,
+/// the others end up in its fallback variant.
+///
+/// Tags are the variant names, they are deserialized through a private shadow
+/// enum. Every variant of both is matched on as well, so adding one to either
+/// enum without listing it here fails to compile.
+macro_rules! known_types {
+    ($(#[$meta:meta])* $name:ident: $enum:ident, $fallback:ident, $shadow:ident, [$($variant:ident),* $(,)?]) => {
+        $(#[$meta])*
+        const $name: &[&str] = &[$(stringify!($variant)),*];
+
+        const _: () = {
+            #[allow(dead_code)]
+            fn exhaustive(value: &$enum, shadow: &$shadow) {
+                match value {
+                    $($enum::$variant { .. } => {})*
+                    $enum::$fallback { .. } => {}
+                }
+                match shadow {
+                    $($shadow::$variant { .. } => {})*
+                }
+            }
+        };
+    };
+}
@@ -1,7 +1,7 @@
 use iso8601_timestamp::Timestamp;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::attachment::Attachment;
+use crate::{attachment::Attachment, user::User};
 
 /// Composite primary key consisting of server and user id
 #[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
@@ -56,10 +56,20 @@ pub struct PartialMember {
 }
 
 /// Optional fields on server member object
-#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub enum FieldsMember {
     Nickname,
     Avatar,
     Roles,
     Timeout,
 }
+
+/// Response used when all members of a server are fetched
+#[derive(Deserialize, Debug, Clone)]
+pub struct BulkMemberResponse {
+    /// List of members
+    pub members: Vec<Member>,
+    /// List of users
+    #[serde(default)]
+    pub users: Vec<User>,
+}
@@ -1,12 +1,15 @@
+use iso8601_timestamp::Timestamp;
 use serde::Serialize;
 
 use crate::{
     bot::FieldsBot,
     channel::{ChannelType, FieldsChannel},
     embed::SendableEmbed,
+    emoji::EmojiParent,
+    member::FieldsMember,
     message::{Interactions, Masquerade, MessageSort, Reply},
     permission::{Override, Permission},
-    server::{Category, FieldsServer, SystemMessageChannels},
+    server::{Category, FieldsRole, FieldsServer, SystemMessageChannels},
     user::{FieldsUser, PartialUserProfile, UserStatus},
 };
 
@@ -32,6 +35,11 @@ pub struct SendMessagePayload {
     /// Information about how this message should be interacted with
     #[serde(skip_serializing_if = "Option::is_none")]
     pub interactions: Option<Interactions>,
+    /// Unique value that keeps the message from being sent twice
+    ///
+    /// Sent as the `Idempotency-Key` header, which also makes sending safe to retry.
+    #[serde(skip)]
+    pub nonce: Option<String>,
 }
 
 /// User data
@@ -202,23 +210,23 @@ pub struct RemoveReactionToMessagePayload {
 #[derive(Serialize, Debug, Clone)]
 pub struct CreateGroupPayload {
     /// Group name
-    name: String,
+    pub name: String,
     /// Group description
-    description: Option<String>,
+    pub description: Option<String>,
     /// Array of user IDs to add to the group
     ///
     /// Must be friends with these users.
-    users: Vec<String>,
+    pub users: Vec<String>,
     /// Whether this group is age-restricted
     #[serde(skip_serializing_if = "Option::is_none")]
-    nsfw: Option<bool>,
+    pub nsfw: Option<bool>,
 }
 
 /// Bot create data
 #[derive(Serialize, Debug, Clone)]
 pub struct CreateBotPayload {
     /// Bot username
-    name: String,
+    pub name: String,
 }
 
 /// Bot invite data
@@ -316,3 +324,94 @@ pub struct CreateChannelPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nsfw: Option<bool>,
 }
+
+/// Query parameters
+#[derive(Serialize, Debug, Clone)]
+pub struct FetchMembersPayload {
+    /// Whether to exclude offline users
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_offline: Option<bool>,
+}
+
+/// Member edit data
+#[derive(Serialize, Debug, Clone)]
+pub struct EditMemberPayload {
+    /// Member nickname
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<String>,
+    /// Attachment Id to set for avatar
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+    /// Array of role ids
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Vec<String>>,
+    /// Timestamp this member is timed out until
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<Timestamp>,
+    /// Fields to remove from member object
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remove: Option<Vec<FieldsMember>>,
+}
+
+/// Information for new server ban
+#[derive(Serialize, Debug, Clone)]
+pub struct BanUserPayload {
+    /// Ban reason
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Role create data
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateRolePayload {
+    /// Role name
+    pub name: String,
+    /// Ranking position
+    ///
+    /// Smaller values take priority.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<i64>,
+}
+
+/// Role edit data
+#[derive(Serialize, Debug, Clone)]
+pub struct EditRolePayload {
+    /// Role name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Role colour
+    ///
+    /// This can be any valid CSS colour
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colour: Option<String>,
+    /// Whether this role should be displayed separately
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hoist: Option<bool>,
+    /// Ranking position
+    ///
+    /// Smaller values take priority.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<i64>,
+    /// Fields to remove from role object
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remove: Option<Vec<FieldsRole>>,
+}
+
+/// Server default permission payload data
+#[derive(Serialize, Debug, Clone)]
+pub struct SetDefaultServerPermissionPayload {
+    /// Default permissions for all members of the server
+    pub permissions: Permission,
+}
+
+/// Emoji create data
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateEmojiPayload {
+    /// Emoji name
+    pub name: String,
+    /// Parent information
+    pub parent: EmojiParent,
+    /// Whether the emoji is mature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nsfw: Option<bool>,
+}
@@ -107,7 +107,7 @@ pub struct OverrideField {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Override {
     /// Allow bit flags
-    allow: Permission,
+    pub allow: Permission,
     /// Disallow bit flags
-    deny: Permission,
+    pub deny: Permission,
 }
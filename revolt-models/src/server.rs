@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     attachment::Attachment,
+    member::MemberCompositeKey,
     permission::{OverrideField, Permission},
 };
 
@@ -175,7 +176,47 @@ pub enum FieldsServer {
 }
 
 /// Optional fields on server object
-#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub enum FieldsRole {
     Colour,
 }
+
+/// Response used when a role is created
+#[derive(Deserialize, Debug, Clone)]
+pub struct NewRoleResponse {
+    /// Id of the role
+    pub id: String,
+    /// New role
+    pub role: Role,
+}
+
+/// Representation of a server ban on Revolt
+#[derive(Deserialize, Debug, Clone)]
+pub struct ServerBan {
+    /// Unique member id
+    #[serde(rename = "_id")]
+    pub id: MemberCompositeKey,
+    /// Reason for ban creation
+    pub reason: Option<String>,
+}
+
+/// Just enough user information to list bans
+#[derive(Deserialize, Debug, Clone)]
+pub struct BannedUser {
+    /// Id of the banned user
+    #[serde(rename = "_id")]
+    pub id: String,
+    /// Username of the banned user
+    pub username: String,
+    /// Avatar of the banned user
+    pub avatar: Option<Attachment>,
+}
+
+/// Response used when the bans of a server are fetched
+#[derive(Deserialize, Debug, Clone)]
+pub struct BulkBanResponse {
+    /// Users objects
+    pub users: Vec<BannedUser>,
+    /// Ban objects
+    pub bans: Vec<ServerBan>,
+}
@@ -0,0 +1,29 @@
+use revolt_models::ApiError;
+use serde_json::json;
+
+#[test]
+fn known_error() {
+    let error: ApiError =
+        serde_json::from_value(json!({ "type": "TooManyServers", "max": 100 })).unwrap();
+
+    assert_eq!(error, ApiError::TooManyServers { max: 100 });
+}
+
+#[test]
+fn unknown_error_keeps_body() {
+    let raw = json!({ "type": "BrandNewError", "detail": "something" });
+
+    match serde_json::from_value(raw.clone()).unwrap() {
+        ApiError::Unknown {
+            error_type,
+            raw: kept,
+        } => {
+            assert_eq!(error_type, "BrandNewError");
+            assert_eq!(
+                serde_json::from_str::<serde_json::Value>(&kept).unwrap(),
+                raw
+            );
+        }
+        error => panic!("expected unknown error, got {error:?}"),
+    }
+}
@@ -0,0 +1,108 @@
+use revolt_models::event::{ClientToServerEvent, KnownEvent, ServerToClientEvent};
+use serde_json::{json, Value};
+
+fn wire(event: ClientToServerEvent) -> Value {
+    serde_json::to_value(event).unwrap()
+}
+
+#[test]
+fn authenticate() {
+    assert_eq!(
+        wire(ClientToServerEvent::Authenticate {
+            token: "token".to_string()
+        }),
+        json!({ "type": "Authenticate", "token": "token" })
+    );
+}
+
+#[test]
+fn ping() {
+    assert_eq!(
+        wire(ClientToServerEvent::Ping { data: 42 }),
+        json!({ "type": "Ping", "data": 42 })
+    );
+}
+
+#[test]
+fn begin_typing() {
+    assert_eq!(
+        wire(ClientToServerEvent::BeginTyping {
+            channel: "01FVB1ZGCPS8TJ4PD4P7NAFDZA".to_string()
+        }),
+        json!({ "type": "BeginTyping", "channel": "01FVB1ZGCPS8TJ4PD4P7NAFDZA" })
+    );
+}
+
+#[test]
+fn end_typing() {
+    assert_eq!(
+        wire(ClientToServerEvent::EndTyping {
+            channel: "01FVB1ZGCPS8TJ4PD4P7NAFDZA".to_string()
+        }),
+        json!({ "type": "EndTyping", "channel": "01FVB1ZGCPS8TJ4PD4P7NAFDZA" })
+    );
+}
+
+#[test]
+fn subscribe() {
+    assert_eq!(
+        wire(ClientToServerEvent::Subscribe {
+            server_id: "01F7ZSBSFHQ8TA81725KQCSDDP".to_string()
+        }),
+        json!({ "type": "Subscribe", "server_id": "01F7ZSBSFHQ8TA81725KQCSDDP" })
+    );
+}
+
+#[test]
+fn unknown_event_keeps_payload() {
+    let raw = json!({ "type": "BrandNewEvent", "id": "01FVB1ZGCPS8TJ4PD4P7NAFDZA" });
+
+    match serde_json::from_value(raw.clone()).unwrap() {
+        ServerToClientEvent::Unknown {
+            event_type,
+            raw: kept,
+        } => {
+            assert_eq!(event_type, "BrandNewEvent");
+            assert_eq!(kept, raw);
+        }
+        event => panic!("expected unknown event, got {event:?}"),
+    }
+}
+
+#[test]
+fn malformed_known_event_fails() {
+    let raw = json!({ "type": "ChannelStartTyping", "id": 42 });
+
+    assert!(serde_json::from_value::<ServerToClientEvent>(raw).is_err());
+}
+
+#[test]
+fn unknown_event_in_bulk() {
+    let raw = json!({
+        "type": "Bulk",
+        "v": [{ "type": "Authenticated" }, { "type": "BrandNewEvent" }]
+    });
+
+    match serde_json::from_value(raw).unwrap() {
+        ServerToClientEvent::Bulk { v } => {
+            assert!(matches!(v[0], ServerToClientEvent::Authenticated));
+            assert!(
+                matches!(&v[1], ServerToClientEvent::Unknown { event_type, .. } if event_type == "BrandNewEvent")
+            );
+        }
+        event => panic!("expected bulk event, got {event:?}"),
+    }
+}
+
+#[test]
+fn known_event_decodes_known_types_only() {
+    let known = r#"{ "type": "ChannelStartTyping", "id": "01FVB1ZGCPS8TJ4PD4P7NAFDZA", "user": "01FVB1ZGCPS8TJ4PD4P7NAFDZB" }"#;
+
+    match serde_json::from_str(known).unwrap() {
+        KnownEvent(ServerToClientEvent::ChannelStartTyping { id, .. }) => {
+            assert_eq!(id, "01FVB1ZGCPS8TJ4PD4P7NAFDZA")
+        }
+        event => panic!("expected typing event, got {event:?}"),
+    }
+    assert!(serde_json::from_str::<KnownEvent>(r#"{ "type": "BrandNewEvent" }"#).is_err());
+}
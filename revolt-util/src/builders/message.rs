@@ -3,11 +3,15 @@ use revolt_models::payload::SendMessagePayload;
 #[derive(Clone, Debug)]
 pub struct SendMessagePayloadBuilder {
     content: Option<String>,
+    nonce: Option<String>,
 }
 
 impl SendMessagePayloadBuilder {
     pub fn new() -> Self {
-        SendMessagePayloadBuilder { content: None }
+        SendMessagePayloadBuilder {
+            content: None,
+            nonce: None,
+        }
     }
 
     pub fn content(mut self, content: impl Into<String>) -> Self {
@@ -15,6 +19,12 @@ impl SendMessagePayloadBuilder {
         self
     }
 
+    /// Unique value that keeps the message from being sent twice, e.g. on retries.
+    pub fn nonce(mut self, nonce: impl Into<String>) -> Self {
+        self.nonce = Some(nonce.into());
+        self
+    }
+
     pub fn build(self) -> SendMessagePayload {
         SendMessagePayload {
             content: self.content,
@@ -24,6 +34,7 @@ impl SendMessagePayloadBuilder {
             embeds: None,
             masquerade: None,
             interactions: None,
+            nonce: self.nonce,
         }
     }
 }